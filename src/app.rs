@@ -1,7 +1,28 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use crossterm::event::{KeyCode, KeyEvent};
+use std::path::{Path, PathBuf};
 
-use crate::{config::Config, history::History, input::InputHandler, stats::Stats, test::Test};
+use crate::{
+    code::{self, CodeLanguage, CodeSource},
+    config::Config,
+    daily,
+    file_picker::FilePicker,
+    ghost::{compare_sections, GhostRun, GhostStore, SectionSplit, SECTION_WORDS},
+    history::History,
+    input::{ErrorPolicy, InputHandler},
+    key_profile::{KeyProfile, UnlockReport, UnlockTarget},
+    lessons::{Lesson, LessonProgress, LESSONS},
+    ngrams::{self, NgramScore},
+    pace::{PaceCaret, PaceTarget},
+    quotes::QuoteLength,
+    stats::{KeyImprovement, Stats},
+    test::{MemoryMode, Test},
+    transforms::TextTransforms,
+    ui::LineLayout,
+    utils::{self, WordSource},
+    wordlists::WordListLibrary,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Screen {
@@ -10,6 +31,40 @@ pub enum Screen {
     Results,
     History,
     ModeSelection,
+    FilePicker,
+    Lessons,
+    Daily,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModeSelectionColumn {
+    Modes,
+    WordSources,
+    ErrorPolicies,
+    PaceTargets,
+    MemoryModes,
+}
+
+impl ModeSelectionColumn {
+    fn next(self) -> Self {
+        match self {
+            ModeSelectionColumn::Modes => ModeSelectionColumn::WordSources,
+            ModeSelectionColumn::WordSources => ModeSelectionColumn::ErrorPolicies,
+            ModeSelectionColumn::ErrorPolicies => ModeSelectionColumn::PaceTargets,
+            ModeSelectionColumn::PaceTargets => ModeSelectionColumn::MemoryModes,
+            ModeSelectionColumn::MemoryModes => ModeSelectionColumn::Modes,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            ModeSelectionColumn::Modes => ModeSelectionColumn::MemoryModes,
+            ModeSelectionColumn::WordSources => ModeSelectionColumn::Modes,
+            ModeSelectionColumn::ErrorPolicies => ModeSelectionColumn::WordSources,
+            ModeSelectionColumn::PaceTargets => ModeSelectionColumn::ErrorPolicies,
+            ModeSelectionColumn::MemoryModes => ModeSelectionColumn::PaceTargets,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -17,6 +72,16 @@ pub enum TestMode {
     Timed(u32),
     WordCount(u32),
     Text(String),
+    Quote(QuoteLength),
+    WeakKeys(Vec<char>),
+    NgramDrill(Vec<String>),
+    /// Words built only from the letters unlocked so far
+    LetterUnlock,
+    Code(CodeSource),
+    /// Index into `LESSONS`
+    Lesson(usize),
+    /// The challenge everyone gets on this day
+    Daily(NaiveDate),
 }
 
 impl TestMode {
@@ -25,6 +90,23 @@ impl TestMode {
             TestMode::Timed(secs) => format!("Timed ({}s)", secs),
             TestMode::WordCount(words) => format!("Word Count ({})", words),
             TestMode::Text(_) => "Custom Text".to_string(),
+            TestMode::Quote(length) => format!("Quote ({})", length.display_name()),
+            TestMode::Code(CodeSource::Bundled) => "Code".to_string(),
+            TestMode::Code(CodeSource::File { path, .. }) => match path.file_name() {
+                Some(name) => format!("Code ({})", name.to_string_lossy()),
+                None => "Code".to_string(),
+            },
+            TestMode::WeakKeys(keys) => {
+                let keys: Vec<String> = keys.iter().map(|ch| ch.to_string()).collect();
+                format!("Weak Keys ({})", keys.join(" "))
+            }
+            TestMode::NgramDrill(ngrams) => format!("N-gram Drill ({})", ngrams.join(" ")),
+            TestMode::LetterUnlock => "Letter Unlock".to_string(),
+            TestMode::Lesson(index) => match Lesson::get(*index) {
+                Some(lesson) => format!("Lesson {}: {}", index + 1, lesson.name),
+                None => format!("Lesson {}", index + 1),
+            },
+            TestMode::Daily(date) => format!("Daily ({})", date.format("%Y-%m-%d")),
         }
     }
 }
@@ -40,19 +122,69 @@ pub struct App {
 
     pub test: Option<Test>,
     pub input_handler: InputHandler,
+    // Lines of the test text as last drawn, see `LineLayout`
+    pub line_layout: LineLayout,
+    pub pace_caret: Option<PaceCaret>,
     pub current_mode: TestMode,
     pub available_modes: Vec<TestMode>,
     pub selected_mode_index: usize,
+    pub available_word_sources: Vec<WordSource>,
+    pub selected_word_source_index: usize,
+    pub available_error_policies: Vec<ErrorPolicy>,
+    pub selected_error_policy_index: usize,
+    pub available_pace_targets: Vec<PaceTarget>,
+    pub selected_pace_target_index: usize,
+    pub available_memory_modes: Vec<MemoryMode>,
+    pub selected_memory_mode_index: usize,
+    pub mode_selection_column: ModeSelectionColumn,
+    // Set by --seed; every test is then generated from it
+    pub seed: Option<u64>,
+    // Text options toggled in mode selection, saved on Enter
+    pub pending_transforms: TextTransforms,
+    pub pending_blind: bool,
     pub last_stats: Option<Stats>,
+    pub last_mode: Option<TestMode>,
+    // Kept so the same text can be retried against its ghost
+    pub last_test: Option<Test>,
+    pub ghost_report: Option<Vec<SectionSplit>>,
+    // The last run, kept unsaved until its text turns out to be retried
+    pub last_run: Option<GhostRun>,
     pub selected_history_item: usize,
+    // History only lists and averages this mode when set
+    pub history_mode_filter: Option<String>,
+    pub file_picker: FilePicker,
+    pub status_message: Option<String>,
+
+    // Error rates on the drilled keys before a weak key drill, and how the drill compared
+    pub weak_key_baseline: Vec<(char, f64)>,
+    pub weak_key_report: Option<Vec<KeyImprovement>>,
+    pub unlock_report: Option<UnlockReport>,
+    // Slowest and most error-prone bigrams and trigrams of the last test
+    pub ngram_report: Vec<NgramScore>,
+
+    pub lesson_progress: LessonProgress,
+    pub selected_lesson: usize,
+    // Whether the lesson just finished was passed, for the results screen
+    pub lesson_passed: Option<bool>,
 }
 
+// How many recent results feed the weak key drill, and how many keys it targets
+const WEAK_KEY_HISTORY_SPAN: usize = 20;
+const WEAK_KEY_COUNT: usize = 5;
+const NGRAM_REPORT_COUNT: usize = 5;
+
 impl App {
     pub fn new() -> Result<Self> {
         let config = Config::load().map_err(|e| anyhow::anyhow!("{}", e))?;
         let history = History::load().map_err(|e| anyhow::anyhow!("{}", e))?;
+        // Like a broken word list, unreadable lesson progress is reported on the menu
+        let mut status_message = None;
+        let lesson_progress = LessonProgress::load().unwrap_or_else(|e| {
+            status_message = Some(format!("Couldn't load lesson progress: {}", e));
+            LessonProgress::default()
+        });
 
-        let available_modes = vec![
+        let mut available_modes = vec![
             TestMode::Timed(30),
             TestMode::Timed(60),
             TestMode::Timed(120),
@@ -60,11 +192,44 @@ impl App {
             TestMode::WordCount(50),
             TestMode::WordCount(100),
         ];
+        available_modes.extend(QuoteLength::all().map(TestMode::Quote));
+        available_modes.push(TestMode::Code(CodeSource::Bundled));
+        available_modes.push(TestMode::LetterUnlock);
+
+        let mut available_word_sources = WordSource::all();
+        // A broken list file shouldn't stop the app from starting; starting a test with it reports the error
+        let word_lists = WordListLibrary::load().unwrap_or_else(|_| WordListLibrary::bundled());
+        available_word_sources.extend(word_lists.names().into_iter().map(WordSource::List));
+        let selected_word_source_index = available_word_sources
+            .iter()
+            .position(|source| *source == config.test_settings.word_source)
+            .unwrap_or(0);
+        let available_error_policies = ErrorPolicy::all();
+        let selected_error_policy_index = available_error_policies
+            .iter()
+            .position(|policy| *policy == config.test_settings.error_policy)
+            .unwrap_or(0);
+        let available_pace_targets = PaceTarget::all();
+        let selected_pace_target_index = available_pace_targets
+            .iter()
+            .position(|target| *target == config.test_settings.pace_target)
+            .unwrap_or(0);
+        let available_memory_modes = MemoryMode::all();
+        let selected_memory_mode_index = available_memory_modes
+            .iter()
+            .position(|mode| *mode == config.test_settings.memory_mode)
+            .unwrap_or(0);
+        let pending_transforms = TextTransforms::from_settings(&config.test_settings);
+        let config_blind = config.test_settings.blind_mode;
 
         let menu_items = vec![
             "Start Test".to_string(),
             "Change Mode".to_string(),
+            "Practice Weak Keys".to_string(),
+            "Load Text File".to_string(),
             "View History".to_string(),
+            "Lessons".to_string(),
+            "Daily Challenge".to_string(),
             "Quit".to_string(),
         ];
 
@@ -77,16 +242,53 @@ impl App {
             menu_items,
             test: None,
             input_handler: InputHandler::new(),
+            line_layout: LineLayout::default(),
             current_mode: available_modes[0].clone(),
             available_modes,
             selected_mode_index: 0,
+            available_word_sources,
+            selected_word_source_index,
+            available_error_policies,
+            selected_error_policy_index,
+            available_pace_targets,
+            selected_pace_target_index,
+            available_memory_modes,
+            selected_memory_mode_index,
+            pace_caret: None,
+            mode_selection_column: ModeSelectionColumn::Modes,
+            pending_transforms,
+            pending_blind: config_blind,
+            seed: None,
             last_stats: None,
+            last_mode: None,
+            last_test: None,
+            ghost_report: None,
+            last_run: None,
             selected_history_item: 0,
+            history_mode_filter: None,
+            file_picker: FilePicker::new(std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))),
+            status_message,
+            weak_key_baseline: Vec::new(),
+            weak_key_report: None,
+            unlock_report: None,
+            ngram_report: Vec::new(),
+            selected_lesson: lesson_progress.current_lesson(),
+            lesson_progress,
+            lesson_passed: None,
         })
     }
 
     pub fn can_quit(&self) -> bool {
-        matches!(self.current_screen, Screen::Menu | Screen::Results | Screen::History | Screen::ModeSelection)
+        matches!(
+            self.current_screen,
+            Screen::Menu
+                | Screen::Results
+                | Screen::History
+                | Screen::ModeSelection
+                | Screen::FilePicker
+                | Screen::Lessons
+                | Screen::Daily
+        )
     }
 
     pub fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
@@ -96,10 +298,14 @@ impl App {
             Screen::Results => self.handle_results_key(key),
             Screen::History => self.handle_history_key(key),
             Screen::ModeSelection => self.handle_mode_selection_key(key),
+            Screen::FilePicker => self.handle_file_picker_key(key),
+            Screen::Lessons => self.handle_lessons_key(key),
+            Screen::Daily => self.handle_daily_key(key),
         }
     }
 
     fn handle_menu_key(&mut self, key: KeyEvent) -> Result<()> {
+        self.status_message = None;
         match key.code {
             KeyCode::Up | KeyCode::Char('k') => {
                 if self.selected_menu_item > 0 {
@@ -115,14 +321,22 @@ impl App {
                 match self.selected_menu_item {
                     0 => self.start_test()?,
                     1 => self.show_mode_selection(),
-                    2 => self.show_history(),
-                    3 => self.should_quit = true,
+                    2 => self.start_weak_key_practice()?,
+                    3 => self.show_file_picker(),
+                    4 => self.show_history(),
+                    5 => self.show_lessons(),
+                    6 => self.show_daily(),
+                    7 => self.should_quit = true,
                     _ => {}
                 }
             }
             KeyCode::Char('1') => self.start_test()?,
             KeyCode::Char('2') => self.show_mode_selection(),
-            KeyCode::Char('3') => self.show_history(),
+            KeyCode::Char('3') => self.start_weak_key_practice()?,
+            KeyCode::Char('4') => self.show_file_picker(),
+            KeyCode::Char('5') => self.show_history(),
+            KeyCode::Char('6') => self.show_lessons(),
+            KeyCode::Char('7') => self.show_daily(),
             KeyCode::Char('q') => self.should_quit = true,
             _ => {}
         }
@@ -130,21 +344,91 @@ impl App {
     }
 
     fn handle_mode_selection_key(&mut self, key: KeyEvent) -> Result<()> {
+        let (selected, len) = match self.mode_selection_column {
+            ModeSelectionColumn::Modes => (&mut self.selected_mode_index, self.available_modes.len()),
+            ModeSelectionColumn::WordSources => {
+                (&mut self.selected_word_source_index, self.available_word_sources.len())
+            }
+            ModeSelectionColumn::ErrorPolicies => {
+                (&mut self.selected_error_policy_index, self.available_error_policies.len())
+            }
+            ModeSelectionColumn::PaceTargets => {
+                (&mut self.selected_pace_target_index, self.available_pace_targets.len())
+            }
+            ModeSelectionColumn::MemoryModes => {
+                (&mut self.selected_memory_mode_index, self.available_memory_modes.len())
+            }
+        };
         match key.code {
             KeyCode::Up | KeyCode::Char('k') => {
-                if self.selected_mode_index > 0 {
-                    self.selected_mode_index -= 1;
+                if *selected > 0 {
+                    *selected -= 1;
                 }
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                if self.selected_mode_index < self.available_modes.len() - 1 {
-                    self.selected_mode_index += 1;
+                if *selected < len - 1 {
+                    *selected += 1;
                 }
             }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.mode_selection_column = self.mode_selection_column.previous();
+            }
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => {
+                self.mode_selection_column = self.mode_selection_column.next();
+            }
+            KeyCode::Char('p') => self.pending_transforms.punctuation = !self.pending_transforms.punctuation,
+            KeyCode::Char('c') => self.pending_transforms.capitalization = !self.pending_transforms.capitalization,
+            KeyCode::Char('n') => self.pending_transforms.numbers = !self.pending_transforms.numbers,
+            KeyCode::Char('b') => self.pending_blind = !self.pending_blind,
             KeyCode::Enter => {
                 self.current_mode = self.available_modes[self.selected_mode_index].clone();
+                let word_source = self.available_word_sources[self.selected_word_source_index].clone();
+                let error_policy = self.available_error_policies[self.selected_error_policy_index];
+                let pace_target = self.available_pace_targets[self.selected_pace_target_index];
+                let memory_mode = self.available_memory_modes[self.selected_memory_mode_index];
+                let transforms = self.pending_transforms;
+                let blind = self.pending_blind;
+                let settings = &mut self.config.test_settings;
+                if settings.word_source != word_source
+                    || settings.error_policy != error_policy
+                    || settings.pace_target != pace_target
+                    || settings.memory_mode != memory_mode
+                    || TextTransforms::from_settings(settings) != transforms
+                    || settings.blind_mode != blind
+                {
+                    settings.word_source = word_source;
+                    settings.error_policy = error_policy;
+                    settings.pace_target = pace_target;
+                    settings.memory_mode = memory_mode;
+                    settings.punctuation = transforms.punctuation;
+                    settings.capitalization = transforms.capitalization;
+                    settings.numbers = transforms.numbers;
+                    settings.blind_mode = blind;
+                    self.config.save().map_err(|e| anyhow::anyhow!("{}", e))?;
+                }
+                self.current_screen = Screen::Menu;
+            }
+            KeyCode::Esc | KeyCode::Char('m') => {
                 self.current_screen = Screen::Menu;
             }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_file_picker_key(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.file_picker.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.file_picker.select_next(),
+            KeyCode::Backspace | KeyCode::Char('h') => self.file_picker.go_to_parent(),
+            KeyCode::Enter => {
+                if let Some(path) = self.file_picker.open_selected() {
+                    match self.load_practice_file(&path, None) {
+                        Ok(()) => self.current_screen = Screen::Menu,
+                        Err(e) => self.file_picker.message = Some(e.to_string()),
+                    }
+                }
+            }
             KeyCode::Esc | KeyCode::Char('m') => {
                 self.current_screen = Screen::Menu;
             }
@@ -153,37 +437,161 @@ impl App {
         Ok(())
     }
 
+    /// Source files, or any file with an explicit language, become a code test;
+    /// anything else is loaded as plain text.
+    pub fn load_practice_file(&mut self, path: &Path, language: Option<CodeLanguage>) -> Result<()> {
+        if language.is_some() || CodeLanguage::from_path(path).is_some() {
+            // Fail now rather than when the test starts
+            code::load_snippet(path, language, &mut rand::thread_rng()).map_err(|e| anyhow::anyhow!("{}", e))?;
+            let path = path.to_path_buf();
+            self.set_custom_mode(TestMode::Code(CodeSource::File { path, language }));
+        } else {
+            let options = self.config.test_settings.text_load_options();
+            let text = utils::load_text_file(path, &options).map_err(|e| anyhow::anyhow!("{}", e))?;
+            self.set_custom_text(text);
+        }
+        Ok(())
+    }
+
+    pub fn set_custom_text(&mut self, text: String) {
+        self.set_custom_mode(TestMode::Text(text));
+    }
+
+    /// Makes a loaded mode current and keeps it selectable from the mode list,
+    /// replacing an earlier one of the same kind.
+    fn set_custom_mode(&mut self, mode: TestMode) {
+        let is_file_code = |m: &TestMode| matches!(m, TestMode::Code(CodeSource::File { .. }));
+        if is_file_code(&mode) {
+            self.available_modes.retain(|m| !is_file_code(m));
+        } else {
+            self.available_modes.retain(|m| !matches!(m, TestMode::Text(_)));
+        }
+        self.available_modes.push(mode.clone());
+        self.selected_mode_index = self.available_modes.len() - 1;
+        self.current_mode = mode;
+    }
+
     fn handle_test_key(&mut self, key: KeyEvent) -> Result<()> {
+        // Opening an official daily attempt uses it up, even if it's left before typing
+        if key.code == KeyCode::Esc
+            && let Some(test) = &self.test
+            && self.is_official_daily(test)
+        {
+            return self.finish_test(Some("Abandoned".to_string()));
+        }
         if key.code == KeyCode::Esc {
             self.current_screen = Screen::Menu;
             self.test = None;
             return Ok(());
         }
 
+        // Keys pressed after the time is up, before the tick ends the test, don't count
+        if self.test.as_ref().is_some_and(|test| test.is_complete()) {
+            return self.finish_test(None);
+        }
         if let Some(test) = &mut self.test {
-            let text_before = self.input_handler.get_typed_text().to_string();
-            
             self.input_handler.handle_key(key, test).map_err(|e| anyhow::anyhow!("{}", e))?;
-            
-            let text_after = self.input_handler.get_typed_text();
-            let target_text = test.get_text();
+        }
+        if let Some(test) = &self.test {
+            if let Some(reason) = self.failure_reason(test) {
+                self.finish_test(Some(reason))?;
+            // Check if test is complete - either time ran out OR user submitted the last word
+            } else if test.is_complete() || self.input_handler.is_finished(test) {
+                self.finish_test(None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Called by the event loop on every tick, whether or not a key was pressed.
+    pub fn on_tick(&mut self) -> Result<()> {
+        if self.current_screen == Screen::Test
+            && let Some(test) = &self.test
+        {
+            // Minimum WPM can be crossed just by pausing
+            if let Some(reason) = self.failure_reason(test) {
+                self.finish_test(Some(reason))?;
+            } else if test.is_complete() {
+                self.finish_test(None)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn failure_reason(&self, test: &Test) -> Option<String> {
+        if !test.is_started() {
+            return None;
+        }
+        let elapsed = test.elapsed_time();
+        let live_stats = self.input_handler.get_live_stats(elapsed);
+        test.get_failure_rules().check(&live_stats, elapsed)
+    }
+
+    fn finish_test(&mut self, fail_reason: Option<String>) -> Result<()> {
+        if let Some(test) = self.test.take() {
+            let mut stats = Stats::calculate(&test, &self.input_handler);
+            stats.failed = fail_reason.is_some();
+            stats.fail_reason = fail_reason;
+            if let TestMode::Daily(date) = test.get_mode()
+                && self.is_official_daily(&test)
+            {
+                stats.daily = Some(*date);
+            }
+            self.weak_key_report = match test.get_mode() {
+                TestMode::WeakKeys(_) => Some(stats.compare_key_error_rates(&self.weak_key_baseline)),
+                _ => None,
+            };
+            self.lesson_passed = match test.get_mode() {
+                TestMode::Lesson(index) => Some(self.record_lesson_attempt(*index, &stats)?),
+                _ => None,
+            };
+            self.history.add_result(&stats).map_err(|e| anyhow::anyhow!("{}", e))?;
+            self.unlock_report = match self.update_key_profile(test.get_mode()) {
+                Ok(report) => report,
+                Err(e) => {
+                    self.status_message = Some(format!("Couldn't update the key profile: {}", e));
+                    None
+                }
+            };
+            self.ngram_report = ngrams::worst_ngrams(self.input_handler.get_keystrokes(), NGRAM_REPORT_COUNT);
 
-            // Check if test is complete - either time ran out OR user finished typing the text
-            if test.is_complete() || text_after.len() >= target_text.len() {
-                let stats = Stats::calculate(test, &self.input_handler);
-                self.history.add_result(&stats).map_err(|e| anyhow::anyhow!("{}", e))?;
-                self.last_stats = Some(stats);
-                self.current_screen = Screen::Results;
-                self.test = None;
+            let run = GhostRun::from_timeline(stats.wpm, &self.input_handler.get_timeline());
+            self.ghost_report = match &self.pace_caret {
+                Some(PaceCaret::Ghost(ghost)) => Some(compare_sections(&run, ghost, test.get_words(), SECTION_WORDS)),
+                _ => None,
+            };
+            if !stats.failed && test.is_repeatable() {
+                self.record_ghost(test.get_text_key(), run.clone());
             }
+            self.last_run = (!stats.failed).then_some(run);
+            self.last_test = Some(test);
+            self.last_stats = Some(stats);
+            self.current_screen = Screen::Results;
         }
         Ok(())
     }
 
     fn handle_results_key(&mut self, key: KeyEvent) -> Result<()> {
+        self.status_message = None;
         match key.code {
             KeyCode::Char('r') | KeyCode::Char('R') => {
-                self.start_test()?;
+                let mode = self.last_mode.clone().unwrap_or_else(|| self.current_mode.clone());
+                self.start_test_with_mode(mode)?;
+            }
+            KeyCode::Char('t') | KeyCode::Char('T') => {
+                if let Some(last_test) = &self.last_test {
+                    let was_repeatable = last_test.is_repeatable();
+                    let test = last_test.restart();
+                    // A one-off text is worth a ghost once it's retried
+                    if !was_repeatable && let Some(run) = self.last_run.take() {
+                        self.record_ghost(test.get_text_key(), run);
+                    }
+                    self.begin_test(test)?;
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') if !self.ngram_report.is_empty() => {
+                let ngrams = self.ngram_report.iter().map(|score| score.ngram.clone()).collect();
+                self.start_test_with_mode(TestMode::NgramDrill(ngrams))?;
             }
             KeyCode::Char('m') | KeyCode::Char('M') | KeyCode::Esc => {
                 self.current_screen = Screen::Menu;
@@ -193,6 +601,46 @@ impl App {
         Ok(())
     }
 
+    fn handle_lessons_key(&mut self, key: KeyEvent) -> Result<()> {
+        self.status_message = None;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') if self.selected_lesson > 0 => {
+                self.selected_lesson -= 1;
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.selected_lesson < LESSONS.len() - 1 => {
+                self.selected_lesson += 1;
+            }
+            KeyCode::Enter => {
+                if self.lesson_progress.is_unlocked(self.selected_lesson) {
+                    self.start_test_with_mode(TestMode::Lesson(self.selected_lesson))?;
+                } else {
+                    self.status_message = Some("Pass the previous lesson to unlock this one".to_string());
+                }
+            }
+            KeyCode::Char('m') | KeyCode::Esc => {
+                self.current_screen = Screen::Menu;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_daily_key(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter => self.start_test_with_mode(TestMode::Daily(daily::today()))?,
+            KeyCode::Char('m') | KeyCode::Esc => {
+                self.current_screen = Screen::Menu;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The first attempt at a day's challenge is the official one.
+    fn is_official_daily(&self, test: &Test) -> bool {
+        matches!(test.get_mode(), TestMode::Daily(date) if self.history.get_daily_result(*date).is_none())
+    }
+
     fn handle_history_key(&mut self, key: KeyEvent) -> Result<()> {
         match key.code {
             KeyCode::Up | KeyCode::Char('k') => {
@@ -201,11 +649,19 @@ impl App {
                 }
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                let max_items = self.history.get_results().len();
+                let max_items = self.history.get_filtered_results(self.history_mode_filter.as_deref()).len();
                 if max_items > 0 && self.selected_history_item < max_items - 1 {
                     self.selected_history_item += 1;
                 }
             }
+            KeyCode::Char('f') => {
+                let settings = &mut self.config.test_settings;
+                settings.include_failed_in_averages = !settings.include_failed_in_averages;
+                self.config.save().map_err(|e| anyhow::anyhow!("{}", e))?;
+            }
+            KeyCode::Left | KeyCode::Right | KeyCode::Char('h') | KeyCode::Char('l') => {
+                self.cycle_history_mode_filter(matches!(key.code, KeyCode::Right | KeyCode::Char('l')));
+            }
             KeyCode::Char('m') | KeyCode::Esc => {
                 self.current_screen = Screen::Menu;
             }
@@ -214,19 +670,157 @@ impl App {
         Ok(())
     }
 
+    /// Steps through "all modes" and then each mode in the history.
+    fn cycle_history_mode_filter(&mut self, forward: bool) {
+        let mut filters: Vec<Option<String>> = vec![None];
+        filters.extend(self.history.get_test_modes().into_iter().map(|mode| Some(mode.to_string())));
+        let current = filters.iter().position(|filter| *filter == self.history_mode_filter).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % filters.len()
+        } else {
+            (current + filters.len() - 1) % filters.len()
+        };
+        self.history_mode_filter = filters.swap_remove(next);
+        self.selected_history_item = 0;
+    }
+
     fn start_test(&mut self) -> Result<()> {
-        self.test = Some(Test::new(&self.current_mode, &self.config).map_err(|e| anyhow::anyhow!("{}", e))?);
+        self.start_test_with_mode(self.current_mode.clone())
+    }
+
+    /// Starts a test without changing the mode selected for regular tests.
+    fn start_test_with_mode(&mut self, mode: TestMode) -> Result<()> {
+        let test = match self.seed {
+            Some(seed) => Test::with_seed(&mode, &self.config, seed),
+            None => Test::new(&mode, &self.config),
+        };
+        match test {
+            Ok(test) => self.begin_test(test),
+            // A broken word list, quote or key profile file is reported rather than ending the app
+            Err(e) => {
+                self.status_message = Some(format!("Couldn't start the test: {}", e));
+                if self.current_screen != Screen::Lessons {
+                    self.current_screen = Screen::Menu;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn begin_test(&mut self, test: Test) -> Result<()> {
+        let mode = test.get_mode().clone();
+        self.status_message = None;
+        self.pace_caret = match self.find_ghost(&test) {
+            Some(ghost) => Some(PaceCaret::Ghost(ghost)),
+            None => self.pace_wpm(&test.get_mode_name()).and_then(PaceCaret::new),
+        };
         self.input_handler = InputHandler::new();
+        self.line_layout = LineLayout::default();
+        self.test = Some(test);
+        self.last_mode = Some(mode);
         self.current_screen = Screen::Test;
         Ok(())
     }
 
+    // An unreadable ghosts file is reported and the run goes on without a ghost
+    fn find_ghost(&mut self, test: &Test) -> Option<GhostRun> {
+        if !self.config.test_settings.ghost_replay {
+            return None;
+        }
+        match GhostStore::load() {
+            Ok(store) => store.get(test.get_text_key()).cloned(),
+            Err(e) => {
+                self.status_message = Some(format!("Couldn't load ghosts: {}", e));
+                None
+            }
+        }
+    }
+
+    fn record_ghost(&mut self, text_key: &str, run: GhostRun) {
+        if !self.config.test_settings.ghost_replay {
+            return;
+        }
+        let saved = GhostStore::load().and_then(|mut store| {
+            if store.record(text_key, run) {
+                store.save()?;
+            }
+            Ok(())
+        });
+        if let Err(e) = saved {
+            self.status_message = Some(format!("Couldn't save the ghost: {}", e));
+        }
+    }
+
+    /// Speed for the pace caret, or `None` when it's off or there's no history to pace against.
+    fn pace_wpm(&self, mode_name: &str) -> Option<f64> {
+        match self.config.test_settings.pace_target {
+            PaceTarget::Off => None,
+            PaceTarget::Fixed => Some(self.config.test_settings.pace_wpm),
+            PaceTarget::Average => self.history.get_average_wpm_by_mode(mode_name),
+            PaceTarget::PersonalBest => self.history.get_personal_best(mode_name).map(|best| best.wpm),
+        }
+    }
+
+    fn record_lesson_attempt(&mut self, index: usize, stats: &Stats) -> Result<bool> {
+        let passed = self.lesson_progress.record_attempt(index, stats);
+        self.lesson_progress.save().map_err(|e| anyhow::anyhow!("{}", e))?;
+        if passed {
+            self.selected_lesson = self.lesson_progress.current_lesson();
+        }
+        Ok(passed)
+    }
+
+    fn update_key_profile(&self, mode: &TestMode) -> Result<Option<UnlockReport>> {
+        let mut profile = KeyProfile::load().map_err(|e| anyhow::anyhow!("{}", e))?;
+        profile.record_session(self.input_handler.get_keystrokes());
+        let report = (*mode == TestMode::LetterUnlock).then(|| {
+            let target = UnlockTarget::from_settings(&self.config.test_settings);
+            let new_letter = profile.unlock_next_letter(&target);
+            UnlockReport {
+                letters: profile.get_unlocked_letters(),
+                new_letter,
+                below_target: profile.letters_below_target(&target),
+            }
+        });
+        profile.save().map_err(|e| anyhow::anyhow!("{}", e))?;
+        Ok(report)
+    }
+
+    fn start_weak_key_practice(&mut self) -> Result<()> {
+        let weak_keys = self.history.get_weak_keys(WEAK_KEY_HISTORY_SPAN, WEAK_KEY_COUNT);
+        if weak_keys.is_empty() {
+            self.status_message = Some("No errors recorded yet - complete a few tests first".to_string());
+            return Ok(());
+        }
+        let keys = weak_keys.iter().map(|&(ch, _)| ch).collect();
+        self.weak_key_baseline = weak_keys;
+        self.start_test_with_mode(TestMode::WeakKeys(keys))
+    }
+
     fn show_mode_selection(&mut self) {
+        self.mode_selection_column = ModeSelectionColumn::Modes;
+        self.pending_transforms = TextTransforms::from_settings(&self.config.test_settings);
+        self.pending_blind = self.config.test_settings.blind_mode;
         self.current_screen = Screen::ModeSelection;
     }
 
+    fn show_file_picker(&mut self) {
+        self.file_picker.refresh();
+        self.current_screen = Screen::FilePicker;
+    }
+
+    fn show_lessons(&mut self) {
+        self.selected_lesson = self.lesson_progress.current_lesson();
+        self.current_screen = Screen::Lessons;
+    }
+
+    fn show_daily(&mut self) {
+        self.current_screen = Screen::Daily;
+    }
+
     fn show_history(&mut self) {
         self.current_screen = Screen::History;
         self.selected_history_item = 0;
+        self.history_mode_filter = None;
     }
 }
\ No newline at end of file
@@ -0,0 +1,160 @@
+use anyhow::{anyhow, bail, Result};
+use std::io::{self, IsTerminal, Read};
+use std::path::PathBuf;
+
+use crate::code::CodeLanguage;
+use crate::utils::{self, TextLoadOptions};
+
+pub const USAGE: &str = "Usage: terminal-type [--file <path>] [--lang <language>] [--seed <n>]
+
+Options:
+  -f, --file <path>  Practice on the text in <path>; source files start a code
+                     test. Use - to read piped stdin
+  -l, --lang <name>  Treat the file as code in this language (rust, python,
+                     javascript, typescript, go, c, c++, java, shell)
+  -s, --seed <n>     Generate every test from seed <n>; the same seed, mode
+                     and settings always give the same text
+  -h, --help         Print this help";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextInput {
+    Stdin,
+    Path(PathBuf),
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    pub text_input: Option<TextInput>,
+    pub language: Option<CodeLanguage>,
+    pub seed: Option<u64>,
+    pub show_help: bool,
+}
+
+impl CliArgs {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut parsed = CliArgs::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => parsed.show_help = true,
+                "-f" | "--file" => {
+                    let value = args.next().ok_or_else(|| anyhow!("{} expects a path or -", arg))?;
+                    parsed.text_input = Some(TextInput::from_arg(&value));
+                }
+                "-l" | "--lang" => {
+                    let value = args.next().ok_or_else(|| anyhow!("{} expects a language name", arg))?;
+                    parsed.language = Some(parse_language(&value)?);
+                }
+                "-s" | "--seed" => {
+                    let value = args.next().ok_or_else(|| anyhow!("{} expects a number", arg))?;
+                    parsed.seed = Some(parse_seed(&value)?);
+                }
+                "-" => parsed.text_input = Some(TextInput::Stdin),
+                _ => {
+                    if let Some(value) = arg.strip_prefix("--file=") {
+                        parsed.text_input = Some(TextInput::from_arg(value));
+                    } else if let Some(value) = arg.strip_prefix("--lang=") {
+                        parsed.language = Some(parse_language(value)?);
+                    } else if let Some(value) = arg.strip_prefix("--seed=") {
+                        parsed.seed = Some(parse_seed(value)?);
+                    } else {
+                        bail!("Unknown argument: {}\n\n{}", arg, USAGE);
+                    }
+                }
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+fn parse_language(name: &str) -> Result<CodeLanguage> {
+    CodeLanguage::from_name(name).ok_or_else(|| anyhow!("Unknown language: {}", name))
+}
+
+fn parse_seed(value: &str) -> Result<u64> {
+    value.parse().map_err(|_| anyhow!("Invalid seed: {}", value))
+}
+
+impl TextInput {
+    fn from_arg(value: &str) -> Self {
+        if value == "-" {
+            TextInput::Stdin
+        } else {
+            TextInput::Path(PathBuf::from(value))
+        }
+    }
+}
+
+/// Reads piped practice text. Must run before raw mode is enabled, since
+/// reading stdin consumes the pipe the text arrives on.
+pub fn read_stdin(options: &TextLoadOptions) -> Result<String> {
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        bail!("Nothing piped to stdin; use `cat notes.txt | terminal-type -`");
+    }
+    let mut raw = String::new();
+    stdin.lock().read_to_string(&mut raw)?;
+    ensure_keyboard_available()?;
+
+    let text = utils::prepare_custom_text(&raw, options);
+    if text.is_empty() {
+        bail!("stdin contains no typeable text");
+    }
+    Ok(text)
+}
+
+/// With stdin used up by the pipe, keyboard events have to come from the
+/// controlling terminal. crossterm reads from `/dev/tty` whenever stdin isn't
+/// a terminal, so just make sure it can be opened and fail early otherwise.
+fn ensure_keyboard_available() -> Result<()> {
+    #[cfg(unix)]
+    std::fs::File::open("/dev/tty").map_err(|e| anyhow!("Could not open /dev/tty for keyboard input: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs> {
+        CliArgs::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_parse_file_argument() {
+        let args = parse(&["--file", "notes.txt"]).unwrap();
+        assert_eq!(args.text_input, Some(TextInput::Path(PathBuf::from("notes.txt"))));
+
+        let args = parse(&["--file=spec.md"]).unwrap();
+        assert_eq!(args.text_input, Some(TextInput::Path(PathBuf::from("spec.md"))));
+    }
+
+    #[test]
+    fn test_parse_stdin_argument() {
+        assert_eq!(parse(&["-"]).unwrap().text_input, Some(TextInput::Stdin));
+        assert_eq!(parse(&["-f", "-"]).unwrap().text_input, Some(TextInput::Stdin));
+    }
+
+    #[test]
+    fn test_parse_language_argument() {
+        let args = parse(&["--file", "build.txt", "--lang", "Rust"]).unwrap();
+        assert_eq!(args.language, Some(CodeLanguage::Rust));
+        assert_eq!(parse(&["--lang=go"]).unwrap().language, Some(CodeLanguage::Go));
+        assert!(parse(&["--lang", "cobol"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_seed_argument() {
+        assert_eq!(parse(&["--seed", "1234"]).unwrap().seed, Some(1234));
+        assert_eq!(parse(&["--seed=7"]).unwrap().seed, Some(7));
+        assert!(parse(&["--seed", "-3"]).is_err());
+        assert!(parse(&[]).unwrap().seed.is_none());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["--file"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+        assert!(parse(&[]).unwrap().text_input.is_none());
+    }
+}
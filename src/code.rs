@@ -0,0 +1,236 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Longest stretch of a source file used for a single test.
+const MAX_SNIPPET_LINES: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodeSource {
+    Bundled,
+    /// A local source file, highlighted as `language` when given and by its
+    /// extension otherwise.
+    File { path: PathBuf, language: Option<CodeLanguage> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Go,
+    C,
+    Cpp,
+    Java,
+    Shell,
+}
+
+impl CodeLanguage {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "rs" => Some(CodeLanguage::Rust),
+            "py" => Some(CodeLanguage::Python),
+            "js" | "mjs" | "cjs" | "jsx" => Some(CodeLanguage::JavaScript),
+            "ts" | "tsx" => Some(CodeLanguage::TypeScript),
+            "go" => Some(CodeLanguage::Go),
+            "c" | "h" => Some(CodeLanguage::C),
+            "cpp" | "cc" | "cxx" | "hpp" | "hh" => Some(CodeLanguage::Cpp),
+            "java" => Some(CodeLanguage::Java),
+            "sh" | "bash" | "zsh" => Some(CodeLanguage::Shell),
+            _ => None,
+        }
+    }
+
+    pub fn all() -> [CodeLanguage; 9] {
+        [
+            CodeLanguage::Rust,
+            CodeLanguage::Python,
+            CodeLanguage::JavaScript,
+            CodeLanguage::TypeScript,
+            CodeLanguage::Go,
+            CodeLanguage::C,
+            CodeLanguage::Cpp,
+            CodeLanguage::Java,
+            CodeLanguage::Shell,
+        ]
+    }
+
+    /// Accepts a display name like "python" or an extension like "py".
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|language| language.display_name().eq_ignore_ascii_case(name))
+            .or_else(|| Self::from_extension(name))
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension().and_then(|ext| ext.to_str()).and_then(Self::from_extension)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CodeLanguage::Rust => "Rust",
+            CodeLanguage::Python => "Python",
+            CodeLanguage::JavaScript => "JavaScript",
+            CodeLanguage::TypeScript => "TypeScript",
+            CodeLanguage::Go => "Go",
+            CodeLanguage::C => "C",
+            CodeLanguage::Cpp => "C++",
+            CodeLanguage::Java => "Java",
+            CodeLanguage::Shell => "Shell",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CodeSnippet {
+    pub name: String,
+    pub language: Option<CodeLanguage>,
+    pub code: String,
+}
+
+// Bundled snippets as (name, language, code)
+pub const BUNDLED_SNIPPETS: &[(&str, CodeLanguage, &str)] = &[
+    ("fizzbuzz.rs", CodeLanguage::Rust, "fn main() {
+    for i in 1..=100 {
+        match (i % 3, i % 5) {
+            (0, 0) => println!(\"FizzBuzz\"),
+            (0, _) => println!(\"Fizz\"),
+            (_, 0) => println!(\"Buzz\"),
+            _ => println!(\"{}\", i),
+        }
+    }
+}"),
+    ("word_count.rs", CodeLanguage::Rust, "use std::collections::HashMap;
+
+fn word_count(text: &str) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for word in text.split_whitespace() {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    counts
+}"),
+    ("binary_search.py", CodeLanguage::Python, "def binary_search(items, target):
+    low, high = 0, len(items) - 1
+    while low <= high:
+        mid = (low + high) // 2
+        if items[mid] == target:
+            return mid
+        elif items[mid] < target:
+            low = mid + 1
+        else:
+            high = mid - 1
+    return -1"),
+    ("debounce.js", CodeLanguage::JavaScript, "function debounce(fn, delay) {
+  let timer = null;
+  return (...args) => {
+    clearTimeout(timer);
+    timer = setTimeout(() => fn(...args), delay);
+  };
+}"),
+    ("handler.go", CodeLanguage::Go, "func healthHandler(w http.ResponseWriter, r *http.Request) {
+\tif r.Method != http.MethodGet {
+\t\thttp.Error(w, \"method not allowed\", http.StatusMethodNotAllowed)
+\t\treturn
+\t}
+\tw.WriteHeader(http.StatusOK)
+\tfmt.Fprintln(w, \"ok\")
+}"),
+];
+
+pub fn random_bundled_snippet(rng: &mut impl Rng) -> CodeSnippet {
+    let (name, language, code) = BUNDLED_SNIPPETS.choose(rng).unwrap_or(&BUNDLED_SNIPPETS[0]);
+    CodeSnippet {
+        name: name.to_string(),
+        language: Some(*language),
+        code: code.to_string(),
+    }
+}
+
+/// Loads a random stretch of at most `MAX_SNIPPET_LINES` lines from a source
+/// file, starting at an unindented line where possible.
+pub fn load_snippet(path: &Path, language: Option<CodeLanguage>, rng: &mut impl Rng) -> Result<CodeSnippet, Box<dyn std::error::Error>> {
+    let raw = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let code = sanitize_code(&raw);
+    let lines: Vec<&str> = code.lines().collect();
+
+    let excerpt = if lines.len() <= MAX_SNIPPET_LINES {
+        code.clone()
+    } else {
+        let starts: Vec<usize> = (0..=lines.len() - MAX_SNIPPET_LINES)
+            .filter(|&i| lines[i].starts_with(|c: char| !c.is_whitespace()))
+            .collect();
+        let start = match starts.choose(rng) {
+            Some(&start) => start,
+            None => rng.gen_range(0..=lines.len() - MAX_SNIPPET_LINES),
+        };
+        sanitize_code(&lines[start..start + MAX_SNIPPET_LINES].join("\n"))
+    };
+
+    if excerpt.trim().is_empty() {
+        return Err(format!("{} contains no typeable code", path.display()).into());
+    }
+
+    Ok(CodeSnippet {
+        name: path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().to_string()),
+        language: language.or_else(|| CodeLanguage::from_path(path)),
+        code: excerpt,
+    })
+}
+
+/// Keeps printable ASCII, tabs and line breaks, strips trailing whitespace and
+/// surrounding blank lines, and removes indentation shared by every line.
+pub fn sanitize_code(text: &str) -> String {
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| {
+            line.chars()
+                .filter(|c| *c == '\t' || *c == ' ' || c.is_ascii_graphic())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    let common_indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let dedented: Vec<&str> = lines
+        .iter()
+        .map(|line| if line.is_empty() { "" } else { &line[common_indent..] })
+        .collect();
+    dedented.join("\n").trim_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_from_path() {
+        assert_eq!(CodeLanguage::from_path(Path::new("src/main.rs")), Some(CodeLanguage::Rust));
+        assert_eq!(CodeLanguage::from_path(Path::new("app.TSX")), Some(CodeLanguage::TypeScript));
+        assert_eq!(CodeLanguage::from_path(Path::new("notes.txt")), None);
+        assert_eq!(CodeLanguage::from_name("c++"), Some(CodeLanguage::Cpp));
+        assert_eq!(CodeLanguage::from_name("py"), Some(CodeLanguage::Python));
+    }
+
+    #[test]
+    fn test_sanitize_code() {
+        let raw = "\n    fn main() {  \r\n        println!(\"hi\");\n\n    }\n\n";
+        assert_eq!(sanitize_code(raw), "fn main() {\n    println!(\"hi\");\n\n}");
+    }
+
+    #[test]
+    fn test_bundled_snippets_are_clean() {
+        for (name, _, code) in BUNDLED_SNIPPETS {
+            assert_eq!(sanitize_code(code), *code, "{}", name);
+        }
+    }
+}
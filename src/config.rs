@@ -3,6 +3,12 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+use crate::highlight::TokenKind;
+use crate::input::ErrorPolicy;
+use crate::pace::PaceTarget;
+use crate::test::MemoryMode;
+use crate::utils::{TextLoadOptions, WordSource};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub theme: Theme,
@@ -22,6 +28,28 @@ pub struct Theme {
     pub border: SerializableColor,
     pub highlight: SerializableColor,
     pub muted: SerializableColor,
+    #[serde(default)]
+    pub syntax: SyntaxColors,
+}
+
+/// Colors for untyped code in code mode, by token kind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyntaxColors {
+    pub keyword: SerializableColor,
+    pub string: SerializableColor,
+    pub comment: SerializableColor,
+    pub number: SerializableColor,
+}
+
+impl Default for SyntaxColors {
+    fn default() -> Self {
+        SyntaxColors {
+            keyword: SerializableColor { r: 198, g: 120, b: 221 },
+            string: SerializableColor { r: 229, g: 192, b: 123 },
+            comment: SerializableColor { r: 92, g: 99, b: 112 },
+            number: SerializableColor { r: 209, g: 154, b: 102 },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,6 +92,7 @@ impl From<Color> for SerializableColor {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TestSettings {
     pub default_mode: String,
     pub default_duration: u32,
@@ -71,6 +100,48 @@ pub struct TestSettings {
     pub sound_enabled: bool,
     pub show_live_wpm: bool,
     pub show_live_accuracy: bool,
+    pub normalize_whitespace: bool,
+    pub max_text_length: Option<usize>,
+    pub word_source: WordSource,
+    /// How strongly generated words lean towards weak keys, from 0 (off) to 1
+    pub adaptive_strength: f64,
+    /// Skip leading indentation instead of typing it
+    pub auto_indent: bool,
+    pub tab_width: usize,
+    pub error_policy: ErrorPolicy,
+    /// Accept unaccented letters for accented ones, like e for é
+    pub ascii_folding: bool,
+    /// Add punctuation, sentence-case capitals and numbers to generated words
+    pub punctuation: bool,
+    pub capitalization: bool,
+    pub numbers: bool,
+    /// End the test as failed on the first error
+    pub sudden_death: bool,
+    /// Fail the test when live accuracy (in percent) or WPM drops below these
+    /// once `failure_grace_seconds` have passed
+    pub min_accuracy: Option<f64>,
+    pub min_wpm: Option<f64>,
+    pub failure_grace_seconds: u32,
+    pub include_failed_in_averages: bool,
+    pub pace_target: PaceTarget,
+    /// Speed of the pace caret when `pace_target` is `Fixed`
+    pub pace_wpm: f64,
+    /// Race the best recorded run on a text when typing it again
+    pub ghost_replay: bool,
+    /// Per-key speed and accuracy (in percent) every unlocked letter needs
+    /// before letter unlock mode adds the next one
+    pub unlock_target_wpm: f64,
+    pub unlock_target_accuracy: f64,
+    /// Hide the text so it has to be typed from memory
+    pub memory_mode: MemoryMode,
+    /// Words previewed at a time in `Preview` memory mode (0 = the whole text),
+    /// and how long each preview stays up
+    pub memory_words: usize,
+    pub memory_seconds: f64,
+    /// Show typed text in a neutral color so mistakes only show up in the results
+    pub blind_mode: bool,
+    /// Also hide live accuracy during blind runs
+    pub blind_hide_accuracy: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -140,6 +211,7 @@ impl Theme {
             border: Color::Gray.into(),
             highlight: Color::LightCyan.into(),
             muted: Color::DarkGray.into(),
+            syntax: SyntaxColors::default(),
         }
     }
 
@@ -155,6 +227,12 @@ impl Theme {
             border: Color::Gray.into(),
             highlight: Color::LightBlue.into(),
             muted: Color::Gray.into(),
+            syntax: SyntaxColors {
+                keyword: SerializableColor { r: 166, g: 38, b: 164 },
+                string: SerializableColor { r: 152, g: 104, b: 1 },
+                comment: SerializableColor { r: 160, g: 161, b: 167 },
+                number: SerializableColor { r: 1, g: 132, b: 188 },
+            },
         }
     }
 
@@ -170,6 +248,12 @@ impl Theme {
             border: SerializableColor { r: 117, g: 113, b: 94 },
             highlight: SerializableColor { r: 102, g: 217, b: 239 },
             muted: SerializableColor { r: 117, g: 113, b: 94 },
+            syntax: SyntaxColors {
+                keyword: SerializableColor { r: 174, g: 129, b: 255 },
+                string: SerializableColor { r: 230, g: 219, b: 116 },
+                comment: SerializableColor { r: 117, g: 113, b: 94 },
+                number: SerializableColor { r: 253, g: 151, b: 31 },
+            },
         }
     }
 
@@ -185,6 +269,12 @@ impl Theme {
             border: SerializableColor { r: 88, g: 110, b: 117 },
             highlight: SerializableColor { r: 38, g: 139, b: 210 },
             muted: SerializableColor { r: 88, g: 110, b: 117 },
+            syntax: SyntaxColors {
+                keyword: SerializableColor { r: 108, g: 113, b: 196 },
+                string: SerializableColor { r: 42, g: 161, b: 152 },
+                comment: SerializableColor { r: 88, g: 110, b: 117 },
+                number: SerializableColor { r: 211, g: 54, b: 130 },
+            },
         }
     }
 
@@ -200,6 +290,12 @@ impl Theme {
             border: SerializableColor { r: 68, g: 71, b: 90 },
             highlight: SerializableColor { r: 139, g: 233, b: 253 },
             muted: SerializableColor { r: 98, g: 114, b: 164 },
+            syntax: SyntaxColors {
+                keyword: SerializableColor { r: 255, g: 121, b: 198 },
+                string: SerializableColor { r: 241, g: 250, b: 140 },
+                comment: SerializableColor { r: 98, g: 114, b: 164 },
+                number: SerializableColor { r: 189, g: 147, b: 249 },
+            },
         }
     }
 
@@ -224,6 +320,18 @@ impl Theme {
     pub fn border(&self) -> Color { self.border.clone().into() }
     pub fn highlight(&self) -> Color { self.highlight.clone().into() }
     pub fn muted(&self) -> Color { self.muted.clone().into() }
+
+    /// Color for an untyped code char, or `None` for plain text.
+    pub fn syntax(&self, kind: TokenKind) -> Option<Color> {
+        let color = match kind {
+            TokenKind::Plain => return None,
+            TokenKind::Keyword => &self.syntax.keyword,
+            TokenKind::String => &self.syntax.string,
+            TokenKind::Comment => &self.syntax.comment,
+            TokenKind::Number => &self.syntax.number,
+        };
+        Some(color.clone().into())
+    }
 }
 
 impl Default for TestSettings {
@@ -235,6 +343,41 @@ impl Default for TestSettings {
             sound_enabled: false,
             show_live_wpm: true,
             show_live_accuracy: true,
+            normalize_whitespace: true,
+            max_text_length: Some(3000),
+            word_source: WordSource::Easy,
+            adaptive_strength: 0.3,
+            auto_indent: true,
+            tab_width: 4,
+            error_policy: ErrorPolicy::Off,
+            ascii_folding: false,
+            punctuation: false,
+            capitalization: false,
+            numbers: false,
+            sudden_death: false,
+            min_accuracy: None,
+            min_wpm: None,
+            failure_grace_seconds: 5,
+            include_failed_in_averages: false,
+            pace_target: PaceTarget::Off,
+            pace_wpm: 60.0,
+            ghost_replay: true,
+            unlock_target_wpm: 35.0,
+            unlock_target_accuracy: 95.0,
+            memory_mode: MemoryMode::Off,
+            memory_words: 10,
+            memory_seconds: 5.0,
+            blind_mode: false,
+            blind_hide_accuracy: true,
+        }
+    }
+}
+
+impl TestSettings {
+    pub fn text_load_options(&self) -> TextLoadOptions {
+        TextLoadOptions {
+            normalize_whitespace: self.normalize_whitespace,
+            max_length: self.max_text_length,
         }
     }
 }
@@ -301,6 +444,26 @@ r = 64
 g = 64
 b = 64
 
+[theme.syntax.keyword]
+r = 198
+g = 120
+b = 221
+
+[theme.syntax.string]
+r = 229
+g = 192
+b = 123
+
+[theme.syntax.comment]
+r = 92
+g = 99
+b = 112
+
+[theme.syntax.number]
+r = 209
+g = 154
+b = 102
+
 [test_settings]
 default_mode = "Timed30"
 default_duration = 30
@@ -308,6 +471,30 @@ default_word_count = 25
 sound_enabled = false
 show_live_wpm = true
 show_live_accuracy = true
+normalize_whitespace = true
+max_text_length = 3000
+word_source = "Easy"
+adaptive_strength = 0.3
+auto_indent = true
+tab_width = 4
+error_policy = "Off"
+ascii_folding = false
+punctuation = false
+capitalization = false
+numbers = false
+sudden_death = false
+failure_grace_seconds = 5
+include_failed_in_averages = false
+pace_target = "Off"
+pace_wpm = 60.0
+ghost_replay = true
+unlock_target_wpm = 35.0
+unlock_target_accuracy = 95.0
+memory_mode = "Off"
+memory_words = 10
+memory_seconds = 5.0
+blind_mode = false
+blind_hide_accuracy = true
 
 [keybindings]
 quit = "q"
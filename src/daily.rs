@@ -0,0 +1,52 @@
+use chrono::{Datelike, Local, NaiveDate};
+
+use crate::config::TestSettings;
+use crate::input::ErrorPolicy;
+use crate::test::MemoryMode;
+use crate::transforms::TextTransforms;
+use crate::utils::WordSource;
+
+pub const DAILY_WORDS: usize = 40;
+// Fixed rather than read from settings, so everyone gets the same text
+pub const DAILY_WORD_SOURCE: WordSource = WordSource::Medium;
+pub const DAILY_TRANSFORMS: TextTransforms = TextTransforms {
+    punctuation: true,
+    capitalization: true,
+    numbers: false,
+};
+
+/// `settings` with the rules that change how a run is scored or shown switched
+/// off, so official attempts compare fairly.
+pub fn test_settings(settings: &TestSettings) -> TestSettings {
+    TestSettings {
+        error_policy: ErrorPolicy::Off,
+        sudden_death: false,
+        min_accuracy: None,
+        min_wpm: None,
+        memory_mode: MemoryMode::Off,
+        blind_mode: false,
+        ..settings.clone()
+    }
+}
+
+/// The local calendar day, which picks the challenge.
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Seed for a day's challenge, e.g. 20261018 for 18 October 2026.
+pub fn seed_for(date: NaiveDate) -> u64 {
+    date.year() as u64 * 10_000 + date.month() as u64 * 100 + date.day() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_for_date() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        assert_eq!(seed_for(date), 20261018);
+        assert_ne!(seed_for(date), seed_for(date.succ_opt().unwrap()));
+    }
+}
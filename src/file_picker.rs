@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+pub struct FilePicker {
+    current_dir: PathBuf,
+    entries: Vec<FileEntry>,
+    pub selected: usize,
+    pub message: Option<String>,
+}
+
+impl FilePicker {
+    pub fn new(dir: PathBuf) -> Self {
+        let mut picker = Self {
+            current_dir: dir,
+            entries: Vec::new(),
+            selected: 0,
+            message: None,
+        };
+        picker.refresh();
+        picker
+    }
+
+    pub fn refresh(&mut self) {
+        self.selected = 0;
+        self.message = None;
+        match Self::read_entries(&self.current_dir) {
+            Ok(entries) => self.entries = entries,
+            Err(e) => {
+                self.entries = Vec::new();
+                self.message = Some(format!("Could not read {}: {}", self.current_dir.display(), e));
+            }
+        }
+        if let Some(parent) = self.current_dir.parent() {
+            self.entries.insert(0, FileEntry {
+                name: "..".to_string(),
+                path: parent.to_path_buf(),
+                is_dir: true,
+            });
+        }
+    }
+
+    /// Directories first, then files, each sorted by name; dotfiles are hidden.
+    fn read_entries(dir: &Path) -> std::io::Result<Vec<FileEntry>> {
+        let mut entries: Vec<FileEntry> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with('.') {
+                    return None;
+                }
+                let is_dir = entry.path().is_dir();
+                Some(FileEntry { name, path: entry.path(), is_dir })
+            })
+            .collect();
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+        Ok(entries)
+    }
+
+    pub fn get_current_dir(&self) -> &Path {
+        &self.current_dir
+    }
+
+    pub fn get_entries(&self) -> &[FileEntry] {
+        &self.entries
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Opens the selected directory, or returns the selected file's path.
+    pub fn open_selected(&mut self) -> Option<PathBuf> {
+        let entry = self.entries.get(self.selected)?.clone();
+        if entry.is_dir {
+            self.current_dir = entry.path;
+            self.refresh();
+            None
+        } else {
+            Some(entry.path)
+        }
+    }
+
+    pub fn go_to_parent(&mut self) {
+        if let Some(parent) = self.current_dir.parent() {
+            self.current_dir = parent.to_path_buf();
+            self.refresh();
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::utils::grapheme_count;
+
+// Oldest ghosts are dropped past this many texts.
+const MAX_GHOSTS: usize = 200;
+// Words per section in the gained/lost report.
+pub const SECTION_WORDS: usize = 10;
+
+/// How far into the text a run was (in pace caret units) after each keystroke.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GhostFrame {
+    pub at_ms: u64,
+    pub position: usize,
+}
+
+/// A recorded run that can be raced again on the same text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GhostRun {
+    pub wpm: f64,
+    pub timestamp: DateTime<Utc>,
+    pub frames: Vec<GhostFrame>,
+}
+
+impl GhostRun {
+    pub fn from_timeline(wpm: f64, timeline: &[(Duration, usize)]) -> Self {
+        Self {
+            wpm,
+            timestamp: Utc::now(),
+            frames: timeline
+                .iter()
+                .map(|&(at, position)| GhostFrame { at_ms: at.as_millis() as u64, position })
+                .collect(),
+        }
+    }
+
+    pub fn position_at(&self, elapsed: Duration) -> usize {
+        let elapsed_ms = elapsed.as_millis() as u64;
+        let reached = self.frames.partition_point(|frame| frame.at_ms <= elapsed_ms);
+        reached.checked_sub(1).map_or(0, |i| self.frames[i].position)
+    }
+
+    /// When the run first got `position` chars in, if it ever did.
+    pub fn time_to_reach(&self, position: usize) -> Option<Duration> {
+        if position == 0 {
+            return Some(Duration::ZERO);
+        }
+        self.frames
+            .iter()
+            .find(|frame| frame.position >= position)
+            .map(|frame| Duration::from_millis(frame.at_ms))
+    }
+}
+
+/// Time gained (negative) or lost (positive) against a ghost over a stretch of words.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionSplit {
+    pub first_word: usize,
+    pub last_word: usize,
+    pub delta_secs: f64,
+}
+
+impl SectionSplit {
+    pub fn get_summary(&self) -> String {
+        let words = format!("Words {}-{}", self.first_word + 1, self.last_word + 1);
+        match self.delta_secs {
+            delta if delta < 0.0 => format!("{}: {:.1}s faster", words, -delta),
+            delta if delta > 0.0 => format!("{}: {:.1}s slower", words, delta),
+            _ => format!("{}: even", words),
+        }
+    }
+}
+
+/// Splits `words` into sections and compares how long each run took on each
+/// one. Sections either run didn't finish are left out.
+pub fn compare_sections(run: &GhostRun, ghost: &GhostRun, words: &[String], section_words: usize) -> Vec<SectionSplit> {
+    let mut boundaries = vec![0];
+    let mut position = 0;
+    for (i, word) in words.iter().enumerate() {
+        position += grapheme_count(word) + 1;
+        if (i + 1) % section_words == 0 || i + 1 == words.len() {
+            // The final word doesn't need its separator typed
+            boundaries.push(if i + 1 == words.len() { position - 1 } else { position });
+        }
+    }
+
+    let mut splits = Vec::new();
+    let mut first_word = 0;
+    for pair in boundaries.windows(2) {
+        let last_word = (first_word + section_words).min(words.len()) - 1;
+        let section_time = |r: &GhostRun| Some(r.time_to_reach(pair[1])?.saturating_sub(r.time_to_reach(pair[0])?));
+        match (section_time(run), section_time(ghost)) {
+            (Some(own), Some(theirs)) => splits.push(SectionSplit {
+                first_word,
+                last_word,
+                delta_secs: own.as_secs_f64() - theirs.as_secs_f64(),
+            }),
+            _ => break,
+        }
+        first_word = last_word + 1;
+    }
+    splits
+}
+
+/// Best recorded run per text, keyed by `text_key`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GhostStore {
+    runs: HashMap<String, GhostRun>,
+}
+
+impl GhostStore {
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let ghosts_path = Self::get_ghosts_path()?;
+        if ghosts_path.exists() {
+            let content = fs::read_to_string(&ghosts_path)?;
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            Ok(GhostStore::default())
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let ghosts_path = Self::get_ghosts_path()?;
+        if let Some(parent) = ghosts_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string(self)?;
+        fs::write(&ghosts_path, content)?;
+        Ok(())
+    }
+
+    fn get_ghosts_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let mut path = dirs::data_dir().or_else(dirs::config_dir).ok_or("Could not find data or config directory")?;
+        path.push("typing-test");
+        path.push("ghosts.json");
+        Ok(path)
+    }
+
+    pub fn get(&self, text_key: &str) -> Option<&GhostRun> {
+        self.runs.get(text_key)
+    }
+
+    /// Keeps `run` if it beats the stored ghost for the text. Returns whether it did.
+    pub fn record(&mut self, text_key: &str, run: GhostRun) -> bool {
+        if self.runs.get(text_key).is_some_and(|best| best.wpm >= run.wpm) {
+            return false;
+        }
+        self.runs.insert(text_key.to_string(), run);
+        while self.runs.len() > MAX_GHOSTS {
+            let oldest = self.runs.iter().min_by_key(|(_, run)| run.timestamp).map(|(key, _)| key.clone());
+            if let Some(key) = oldest {
+                self.runs.remove(&key);
+            }
+        }
+        true
+    }
+}
+
+/// Stable FNV-1a hash of a text, used to find its ghost again across runs.
+pub fn text_key(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(wpm: f64, frames: &[(u64, usize)]) -> GhostRun {
+        let timeline: Vec<(Duration, usize)> =
+            frames.iter().map(|&(ms, position)| (Duration::from_millis(ms), position)).collect();
+        GhostRun::from_timeline(wpm, &timeline)
+    }
+
+    #[test]
+    fn test_ghost_position() {
+        let ghost = run(60.0, &[(0, 1), (200, 2), (400, 4)]);
+        assert_eq!(ghost.position_at(Duration::from_millis(100)), 1);
+        assert_eq!(ghost.position_at(Duration::from_millis(450)), 4);
+        assert_eq!(ghost.time_to_reach(3), Some(Duration::from_millis(400)));
+        assert_eq!(ghost.time_to_reach(5), None);
+    }
+
+    #[test]
+    fn test_compare_sections() {
+        let words: Vec<String> = ["ab", "cd", "ef"].iter().map(|w| w.to_string()).collect();
+        // Sections of two words: "ab cd " ends at 6, "ef" at 8
+        let ghost = run(50.0, &[(1000, 6), (2000, 8)]);
+        let faster = run(60.0, &[(500, 6), (2000, 8)]);
+        let splits = compare_sections(&faster, &ghost, &words, 2);
+        assert_eq!(splits.len(), 2);
+        assert_eq!(splits[0].delta_secs, -0.5);
+        assert_eq!(splits[1].delta_secs, 0.5);
+        assert_eq!(splits[1].first_word, 2);
+        assert_eq!(splits[0].get_summary(), "Words 1-2: 0.5s faster");
+    }
+
+    #[test]
+    fn test_store_keeps_best_run() {
+        let mut store = GhostStore::default();
+        let key = text_key("same text");
+        assert!(store.record(&key, run(50.0, &[])));
+        assert!(!store.record(&key, run(40.0, &[])));
+        assert!(store.record(&key, run(70.0, &[])));
+        assert_eq!(store.get(&key).unwrap().wpm, 70.0);
+        assert_ne!(text_key("same text"), text_key("other text"));
+    }
+}
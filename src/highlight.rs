@@ -0,0 +1,220 @@
+use crate::code::CodeLanguage;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+
+struct Syntax {
+    keywords: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [u8],
+    // Quotes that may run across lines, like JS template literals
+    multiline_quotes: &'static [u8],
+    triple_quotes: bool,
+    // Rust uses ' for both char literals and lifetimes
+    char_literals_only: bool,
+}
+
+const C_COMMENTS: Option<(&str, &str)> = Some(("/*", "*/"));
+
+fn syntax_for(language: CodeLanguage) -> Syntax {
+    match language {
+        CodeLanguage::Rust => Syntax {
+            keywords: &[
+                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+                "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+                "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+                "use", "where", "while",
+            ],
+            line_comments: &["//"],
+            block_comment: C_COMMENTS,
+            quotes: b"\"'",
+            multiline_quotes: b"\"",
+            triple_quotes: false,
+            char_literals_only: true,
+        },
+        CodeLanguage::Python => Syntax {
+            keywords: &[
+                "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+                "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in",
+                "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "self", "try", "while",
+                "with", "yield",
+            ],
+            line_comments: &["#"],
+            block_comment: None,
+            quotes: b"\"'",
+            multiline_quotes: b"",
+            triple_quotes: true,
+            char_literals_only: false,
+        },
+        CodeLanguage::JavaScript | CodeLanguage::TypeScript => Syntax {
+            keywords: &[
+                "as", "async", "await", "break", "case", "catch", "class", "const", "continue", "default",
+                "delete", "do", "else", "enum", "export", "extends", "false", "finally", "for", "from",
+                "function", "if", "implements", "import", "in", "instanceof", "interface", "let", "new", "null",
+                "of", "return", "static", "super", "switch", "this", "throw", "true", "try", "type", "typeof",
+                "undefined", "var", "void", "while", "yield",
+            ],
+            line_comments: &["//"],
+            block_comment: C_COMMENTS,
+            quotes: b"\"'`",
+            multiline_quotes: b"`",
+            triple_quotes: false,
+            char_literals_only: false,
+        },
+        CodeLanguage::Go => Syntax {
+            keywords: &[
+                "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough",
+                "false", "for", "func", "go", "goto", "if", "import", "interface", "map", "nil", "package",
+                "range", "return", "select", "struct", "switch", "true", "type", "var",
+            ],
+            line_comments: &["//"],
+            block_comment: C_COMMENTS,
+            quotes: b"\"'`",
+            multiline_quotes: b"`",
+            triple_quotes: false,
+            char_literals_only: false,
+        },
+        CodeLanguage::C | CodeLanguage::Cpp | CodeLanguage::Java => Syntax {
+            keywords: &[
+                "auto", "bool", "break", "case", "catch", "char", "class", "const", "continue", "default",
+                "delete", "do", "double", "else", "enum", "extends", "extern", "false", "final", "float", "for",
+                "if", "implements", "import", "include", "int", "interface", "long", "namespace", "new",
+                "nullptr", "null", "package", "private", "protected", "public", "return", "short", "signed",
+                "sizeof", "static", "struct", "switch", "template", "this", "throw", "true", "try", "typedef",
+                "union", "unsigned", "using", "virtual", "void", "while",
+            ],
+            line_comments: &["//"],
+            block_comment: C_COMMENTS,
+            quotes: b"\"'",
+            multiline_quotes: b"",
+            triple_quotes: false,
+            char_literals_only: false,
+        },
+        CodeLanguage::Shell => Syntax {
+            keywords: &[
+                "case", "do", "done", "elif", "else", "esac", "exit", "export", "fi", "for", "function", "if",
+                "in", "local", "readonly", "return", "then", "until", "while",
+            ],
+            line_comments: &["#"],
+            block_comment: None,
+            quotes: b"\"'",
+            multiline_quotes: b"\"'",
+            triple_quotes: false,
+            char_literals_only: false,
+        },
+    }
+}
+
+/// Classifies every byte of `code`. This is a lightweight lexer rather than a
+/// parser, so it only has to be right about the common cases.
+pub fn highlight(code: &str, language: CodeLanguage) -> Vec<TokenKind> {
+    let syntax = syntax_for(language);
+    let bytes = code.as_bytes();
+    let mut kinds = vec![TokenKind::Plain; bytes.len()];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        let (end, kind) = if syntax.line_comments.iter().any(|prefix| rest.starts_with(prefix.as_bytes())) {
+            let end = rest.iter().position(|&b| b == b'\n').map_or(bytes.len(), |pos| i + pos);
+            (end, TokenKind::Comment)
+        } else if let Some((open, close)) = syntax.block_comment
+            && rest.starts_with(open.as_bytes())
+        {
+            let end = find(bytes, i + open.len(), close.as_bytes()).map_or(bytes.len(), |pos| pos + close.len());
+            (end, TokenKind::Comment)
+        } else if syntax.quotes.contains(&rest[0]) && is_string_start(&syntax, rest) {
+            (string_end(&syntax, bytes, i), TokenKind::String)
+        } else if rest[0].is_ascii_digit() {
+            let len = rest.iter().take_while(|&&b| b.is_ascii_alphanumeric() || b == b'_' || b == b'.').count();
+            (i + len, TokenKind::Number)
+        } else if is_identifier_byte(rest[0]) {
+            let len = rest.iter().take_while(|&&b| is_identifier_byte(b) || b.is_ascii_digit()).count();
+            let word = &code[i..i + len];
+            let kind = if syntax.keywords.contains(&word) { TokenKind::Keyword } else { TokenKind::Plain };
+            (i + len, kind)
+        } else {
+            (i + 1, TokenKind::Plain)
+        };
+
+        kinds[i..end].fill(kind);
+        i = end;
+    }
+    kinds
+}
+
+fn is_identifier_byte(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b == b'$' || !b.is_ascii()
+}
+
+fn is_string_start(syntax: &Syntax, rest: &[u8]) -> bool {
+    if rest[0] != b'\'' || !syntax.char_literals_only {
+        return true;
+    }
+    // 'a' or '\n', but not a lifetime like 'a
+    rest.get(1) == Some(&b'\\') || rest.get(2) == Some(&b'\'')
+}
+
+fn string_end(syntax: &Syntax, bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    if syntax.triple_quotes && bytes[start..].starts_with(&[quote; 3]) {
+        return find(bytes, start + 3, &[quote; 3]).map_or(bytes.len(), |pos| pos + 3);
+    }
+
+    let multiline = syntax.multiline_quotes.contains(&quote);
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\n' if !multiline => return i,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes.get(from..)?.windows(needle.len()).position(|window| window == needle).map(|pos| from + pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds_of(code: &str, language: CodeLanguage, token: &str) -> Vec<TokenKind> {
+        let start = code.find(token).unwrap();
+        highlight(code, language)[start..start + token.len()].to_vec()
+    }
+
+    #[test]
+    fn test_highlight_rust() {
+        let code = "fn main() { let s = \"a // b\"; } // done";
+        assert!(kinds_of(code, CodeLanguage::Rust, "fn").iter().all(|&k| k == TokenKind::Keyword));
+        assert!(kinds_of(code, CodeLanguage::Rust, "\"a // b\"").iter().all(|&k| k == TokenKind::String));
+        assert!(kinds_of(code, CodeLanguage::Rust, "// done").iter().all(|&k| k == TokenKind::Comment));
+        assert!(kinds_of(code, CodeLanguage::Rust, "main").iter().all(|&k| k == TokenKind::Plain));
+    }
+
+    #[test]
+    fn test_rust_lifetimes_are_not_strings() {
+        let code = "fn f<'a>(c: &'a str) -> char { 'x' }";
+        assert_eq!(kinds_of(code, CodeLanguage::Rust, "'a>")[2], TokenKind::Plain);
+        assert!(kinds_of(code, CodeLanguage::Rust, "'x'").iter().all(|&k| k == TokenKind::String));
+    }
+
+    #[test]
+    fn test_highlight_python() {
+        let code = "def f(x):\n    \"\"\"doc\nstring\"\"\"\n    return x + 1  # add";
+        assert!(kinds_of(code, CodeLanguage::Python, "\"\"\"doc\nstring\"\"\"").iter().all(|&k| k == TokenKind::String));
+        assert_eq!(kinds_of(code, CodeLanguage::Python, "1"), vec![TokenKind::Number]);
+        assert!(kinds_of(code, CodeLanguage::Python, "# add").iter().all(|&k| k == TokenKind::Comment));
+    }
+}
@@ -1,7 +1,8 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::stats::Stats;
 
@@ -62,47 +63,143 @@ impl History {
         &self.results
     }
 
+    // Failed runs were cut short, so they never count as a best
+    fn completed_results(&self) -> impl Iterator<Item = &Stats> {
+        self.results.iter().filter(|r| !r.failed)
+    }
+
     pub fn get_best_wpm(&self) -> Option<f64> {
-        self.results.iter().map(|r| r.wpm).fold(None, |max, wpm| {
+        self.completed_results().map(|r| r.wpm).fold(None, |max, wpm| {
             Some(max.map_or(wpm, |m| m.max(wpm)))
         })
     }
 
     pub fn get_best_accuracy(&self) -> Option<f64> {
-        self.results.iter().map(|r| r.accuracy).fold(None, |max, acc| {
+        self.completed_results().map(|r| r.accuracy).fold(None, |max, acc| {
             Some(max.map_or(acc, |m| m.max(acc)))
         })
     }
 
-    pub fn get_average_wpm(&self) -> f64 {
-        if self.results.is_empty() {
+    /// Results of `mode` only, or all of them without one.
+    pub fn get_filtered_results(&self, mode: Option<&str>) -> Vec<&Stats> {
+        self.results.iter().filter(|r| mode.is_none_or(|mode| r.test_mode == mode)).collect()
+    }
+
+    /// Distinct test modes, most recently used first.
+    pub fn get_test_modes(&self) -> Vec<&str> {
+        let mut modes: Vec<&str> = Vec::new();
+        for result in &self.results {
+            if !modes.contains(&result.test_mode.as_str()) {
+                modes.push(&result.test_mode);
+            }
+        }
+        modes
+    }
+
+    fn averaged_results(&self, include_failed: bool, mode: Option<&str>) -> Vec<&Stats> {
+        self.get_filtered_results(mode).into_iter().filter(|r| include_failed || !r.failed).collect()
+    }
+
+    pub fn get_average_wpm(&self, include_failed: bool, mode: Option<&str>) -> f64 {
+        let results = self.averaged_results(include_failed, mode);
+        if results.is_empty() {
             return 0.0;
         }
-        self.results.iter().map(|r| r.wpm).sum::<f64>() / self.results.len() as f64
+        results.iter().map(|r| r.wpm).sum::<f64>() / results.len() as f64
     }
 
-    pub fn get_average_accuracy(&self) -> f64 {
-        if self.results.is_empty() {
+    pub fn get_average_accuracy(&self, include_failed: bool, mode: Option<&str>) -> f64 {
+        let results = self.averaged_results(include_failed, mode);
+        if results.is_empty() {
             return 0.0;
         }
-        self.results.iter().map(|r| r.accuracy).sum::<f64>() / self.results.len() as f64
+        results.iter().map(|r| r.accuracy).sum::<f64>() / results.len() as f64
+    }
+
+    pub fn get_failed_count(&self, mode: Option<&str>) -> usize {
+        self.get_filtered_results(mode).iter().filter(|r| r.failed).count()
     }
 
     pub fn get_recent_results(&self, count: usize) -> Vec<&Stats> {
         self.results.iter().take(count).collect()
     }
 
+    /// Keys with the highest combined error rate over the `recent_count` latest results.
+    pub fn get_weak_keys(&self, recent_count: usize, limit: usize) -> Vec<(char, f64)> {
+        let mut errors: HashMap<char, usize> = HashMap::new();
+        let mut attempts: HashMap<char, usize> = HashMap::new();
+        for result in self.results.iter().take(recent_count) {
+            for (&ch, &count) in &result.error_frequency {
+                *errors.entry(ch).or_insert(0) += count;
+            }
+            for (&ch, &count) in &result.char_attempts {
+                *attempts.entry(ch).or_insert(0) += count;
+            }
+        }
+
+        let mut weak_keys: Vec<(char, usize, f64)> = errors
+            .into_iter()
+            .filter(|&(ch, count)| count > 0 && !ch.is_whitespace())
+            .map(|(ch, count)| {
+                // Results saved before attempts were tracked only know the error count
+                let total = attempts.get(&ch).copied().unwrap_or(0).max(count);
+                (ch, count, count as f64 / total as f64)
+            })
+            .collect();
+        weak_keys.sort_by(|a, b| {
+            b.2.partial_cmp(&a.2)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.1.cmp(&a.1))
+                .then(a.0.cmp(&b.0))
+        });
+        weak_keys.into_iter().take(limit).map(|(ch, _, rate)| (ch, rate)).collect()
+    }
+
     pub fn get_results_by_mode(&self, mode_name: &str) -> Vec<&Stats> {
         self.results.iter().filter(|r| r.test_mode == mode_name).collect()
     }
 
+    /// The official attempt at `date`'s daily challenge, once it has been taken.
+    pub fn get_daily_result(&self, date: NaiveDate) -> Option<&Stats> {
+        self.results.iter().find(|r| r.daily == Some(date))
+    }
+
+    /// Official daily attempts, newest first.
+    pub fn get_daily_results(&self) -> Vec<&Stats> {
+        let mut results: Vec<&Stats> = self.results.iter().filter(|r| r.daily.is_some()).collect();
+        results.sort_by_key(|r| std::cmp::Reverse(r.daily));
+        results
+    }
+
+    /// Consecutive days with an official daily attempt, up to today, or up to
+    /// yesterday while today's challenge is still open.
+    pub fn get_daily_streak(&self, today: NaiveDate) -> usize {
+        let days: HashSet<NaiveDate> = self.results.iter().filter_map(|r| r.daily).collect();
+        let mut day = if days.contains(&today) { Some(today) } else { today.pred_opt() };
+        let mut streak = 0;
+        while let Some(date) = day.filter(|date| days.contains(date)) {
+            streak += 1;
+            day = date.pred_opt();
+        }
+        streak
+    }
+
     pub fn clear(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         self.results.clear();
         self.save()
     }
 
-    pub fn get_personal_best(&self) -> Option<&Stats> {
-        self.results.iter().max_by(|a, b| a.wpm.partial_cmp(&b.wpm).unwrap_or(std::cmp::Ordering::Equal))
+    /// Average net WPM of completed runs in a mode, if there are any.
+    pub fn get_average_wpm_by_mode(&self, mode_name: &str) -> Option<f64> {
+        let wpms: Vec<f64> = self.completed_results().filter(|r| r.test_mode == mode_name).map(|r| r.wpm).collect();
+        if wpms.is_empty() {
+            return None;
+        }
+        Some(wpms.iter().sum::<f64>() / wpms.len() as f64)
+    }
+
+    pub fn get_personal_best(&self, mode_name: &str) -> Option<&Stats> {
+        self.completed_results().filter(|r| r.test_mode == mode_name).max_by(|a, b| a.wpm.partial_cmp(&b.wpm).unwrap_or(std::cmp::Ordering::Equal))
     }
 
     pub fn get_improvement_over_time(&self) -> Vec<(chrono::DateTime<chrono::Utc>, f64)> {
@@ -123,13 +220,13 @@ impl History {
         results
     }
 
-    pub fn get_stats_summary(&self) -> HistorySummary {
+    pub fn get_stats_summary(&self, include_failed: bool) -> HistorySummary {
         HistorySummary {
             total_tests: self.results.len(),
             best_wpm: self.get_best_wpm().unwrap_or(0.0),
             best_accuracy: self.get_best_accuracy().unwrap_or(0.0),
-            average_wpm: self.get_average_wpm(),
-            average_accuracy: self.get_average_accuracy(),
+            average_wpm: self.get_average_wpm(include_failed, None),
+            average_accuracy: self.get_average_accuracy(include_failed, None),
             total_time_spent: self.results.iter().map(|r| r.test_duration.as_secs()).sum::<u64>(),
             most_common_mode: self.get_most_common_mode(),
         }
@@ -174,8 +271,6 @@ impl HistorySummary {
 mod tests {
     use super::*;
     use crate::stats::Stats;
-    use chrono::Utc;
-    use std::time::Duration;
     use std::collections::HashMap;
 
     #[test]
@@ -188,18 +283,13 @@ mod tests {
     fn test_add_result() {
         let mut history = History::default();
         let stats = Stats {
-            timestamp: Utc::now(),
-            test_mode: "Test".to_string(),
             wpm: 50.0,
             raw_wpm: 55.0,
             accuracy: 0.95,
             error_count: 5,
             correct_chars: 95,
-            total_chars: 100,
-            test_duration: Duration::from_secs(60),
-            error_frequency: HashMap::new(),
-            speed_over_time: vec![(0.0, 0.0)],
             consistency_score: 0.8,
+            ..Stats::sample()
         };
         
         history.add_result(&stats).unwrap();
@@ -211,20 +301,7 @@ mod tests {
     fn test_statistics() {
         let mut history = History::default();
         
-        let stats1 = Stats {
-            timestamp: Utc::now(),
-            test_mode: "Test".to_string(),
-            wpm: 40.0,
-            raw_wpm: 45.0,
-            accuracy: 0.90,
-            error_count: 10,
-            correct_chars: 90,
-            total_chars: 100,
-            test_duration: Duration::from_secs(60),
-            error_frequency: HashMap::new(),
-            speed_over_time: vec![(0.0, 0.0)],
-            consistency_score: 0.7,
-        };
+        let stats1 = Stats::sample();
 
         let stats2 = Stats {
             wpm: 60.0,
@@ -234,7 +311,70 @@ mod tests {
         history.add_result(&stats1).unwrap();
         history.add_result(&stats2).unwrap();
         assert_eq!(history.get_best_wpm(), Some(60.0));
-        assert_eq!(history.get_average_wpm(), 50.0);
+        assert_eq!(history.get_average_wpm(true, None), 50.0);
         assert_eq!(history.get_best_accuracy(), Some(0.95));
+
+        let failed = Stats {
+            wpm: 90.0,
+            failed: true,
+            fail_reason: Some("Sudden death: first error".to_string()),
+            ..stats1.clone()
+        };
+        history.add_result(&failed).unwrap();
+        assert_eq!(history.get_best_wpm(), Some(60.0));
+        assert_eq!(history.get_average_wpm(false, None), 50.0);
+        assert_eq!(history.get_average_wpm(true, None), 190.0 / 3.0);
+        assert_eq!(history.get_failed_count(None), 1);
+        assert_eq!(history.get_personal_best("Test").map(|r| r.wpm), Some(60.0));
+        assert_eq!(history.get_average_wpm_by_mode("Test"), Some(50.0));
+        assert!(history.get_personal_best("Other").is_none());
+
+        let other = Stats {
+            wpm: 80.0,
+            test_mode: "Word Count (25) [Blind]".to_string(),
+            timestamp: stats1.timestamp + chrono::Duration::seconds(1),
+            ..stats1.clone()
+        };
+        history.add_result(&other).unwrap();
+        assert_eq!(history.get_test_modes(), vec!["Word Count (25) [Blind]", "Test"]);
+        assert_eq!(history.get_filtered_results(Some("Test")).len(), 3);
+        assert_eq!(history.get_average_wpm(false, Some("Test")), 50.0);
+        assert_eq!(history.get_average_wpm(false, Some("Word Count (25) [Blind]")), 80.0);
+        assert_eq!(history.get_failed_count(Some("Word Count (25) [Blind]")), 0);
+    }
+
+    #[test]
+    fn test_weak_keys() {
+        let stats = Stats {
+            error_frequency: HashMap::from([('e', 2), ('q', 1), (' ', 5)]),
+            char_attempts: HashMap::from([('e', 20), ('q', 2), (' ', 20)]),
+            ..Stats::sample()
+        };
+        let history = History { results: vec![stats.clone(), stats] };
+
+        let weak_keys = history.get_weak_keys(10, 5);
+        assert_eq!(weak_keys, vec![('q', 0.5), ('e', 0.1)]);
+    }
+
+    #[test]
+    fn test_daily_streak() {
+        let practice = Stats {
+            test_mode: "Daily".to_string(),
+            ..Stats::sample()
+        };
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let official = |days_ago: u64| Stats {
+            daily: today.checked_sub_days(chrono::Days::new(days_ago)),
+            ..practice.clone()
+        };
+        let history = History { results: vec![official(1), practice.clone(), official(2), official(4)] };
+        assert_eq!(history.get_daily_streak(today), 2);
+        assert!(history.get_daily_result(today).is_none());
+        assert_eq!(history.get_daily_results().len(), 3);
+
+        let history = History { results: vec![official(0), official(1), official(3)] };
+        assert_eq!(history.get_daily_streak(today), 2);
+        assert_eq!(history.get_daily_results()[0].daily, Some(today));
+        assert_eq!(History::default().get_daily_streak(today), 0);
     }
 }
\ No newline at end of file
@@ -1,20 +1,127 @@
 use crossterm::event::{KeyCode, KeyEvent};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::{test::Test, stats::LiveStats};
+use crate::{test::{Test, WordBreak}, stats::LiveStats, utils::grapheme_count};
 
 #[derive(Debug, Clone)]
 pub struct KeystrokeData {
-    pub character: char,
+    pub expected: Option<char>,
     pub timestamp: Instant,
     pub is_correct: bool,
     pub is_correction: bool,
 }
 
+/// What happens to a wrong keystroke. Rejected keystrokes still count as errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ErrorPolicy {
+    /// Mistakes are typed in and the word can be submitted anyway
+    #[default]
+    Off,
+    /// The cursor only moves on once the correct char is typed
+    StopOnLetter,
+    /// Mistakes are typed in, but the word can't be submitted until fixed
+    StopOnWord,
+}
+
+impl ErrorPolicy {
+    pub fn all() -> Vec<ErrorPolicy> {
+        vec![ErrorPolicy::Off, ErrorPolicy::StopOnLetter, ErrorPolicy::StopOnWord]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ErrorPolicy::Off => "Off",
+            ErrorPolicy::StopOnLetter => "Stop on letter",
+            ErrorPolicy::StopOnWord => "Stop on word",
+        }
+    }
+}
+
+// Caps how far a single word can be overtyped so a stuck key can't flood the text area.
+const MAX_EXTRA_CHARS: usize = 10;
+
+/// How a typed grapheme cluster compares with the one it should match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphemeMatch {
+    Exact,
+    /// A correct start, like `e` typed before a dead key's accent arrives
+    Partial,
+    Wrong,
+}
+
+/// Compares clusters in decomposed form, so composed and decomposed input are
+/// equivalent. With `ascii_folding`, accents are ignored altogether.
+pub fn compare_graphemes(typed: &str, target: &str, ascii_folding: bool) -> GraphemeMatch {
+    let typed = comparable(typed, ascii_folding);
+    let target = comparable(target, ascii_folding);
+    if typed == target {
+        GraphemeMatch::Exact
+    } else if target.starts_with(&typed) {
+        GraphemeMatch::Partial
+    } else {
+        GraphemeMatch::Wrong
+    }
+}
+
+/// Whether a typed word counts as the target word.
+pub fn words_match(typed: &str, target: &str, ascii_folding: bool) -> bool {
+    typed == target || (ascii_folding && comparable(typed, true) == comparable(target, true))
+}
+
+fn comparable(text: &str, ascii_folding: bool) -> String {
+    text.nfd().filter(|&c| !ascii_folding || !is_combining_mark(c)).collect()
+}
+
+/// Whether the last cluster of `typed` is only partly typed.
+fn ends_partial(typed: &str, target: &str, ascii_folding: bool) -> bool {
+    let typed_len = grapheme_count(typed);
+    match (typed.graphemes(true).next_back(), target.graphemes(true).nth(typed_len.saturating_sub(1))) {
+        (Some(last), Some(target_grapheme)) => {
+            compare_graphemes(last, target_grapheme, ascii_folding) == GraphemeMatch::Partial
+        }
+        _ => false,
+    }
+}
+
+/// The target char the next keystroke should produce. Words are compared by
+/// grapheme cluster, so while the last typed cluster is partial the rest of
+/// that cluster comes first.
+fn next_expected(typed: &str, target: &str, ascii_folding: bool) -> Option<char> {
+    let typed_len = grapheme_count(typed);
+    if ends_partial(typed, target, ascii_folding) {
+        let last = comparable(typed.graphemes(true).next_back()?, ascii_folding);
+        let target_grapheme = comparable(target.graphemes(true).nth(typed_len - 1)?, ascii_folding);
+        return target_grapheme[last.len()..].chars().next();
+    }
+    target.graphemes(true).nth(typed_len).and_then(|grapheme| grapheme.chars().next())
+}
+
+/// Whether typing `ch` after `typed` keeps the word on track. Starting a new
+/// cluster while the previous one is unfinished is a mistake.
+fn keystroke_matches(typed: &str, ch: char, target: &str, ascii_folding: bool) -> bool {
+    let candidate: String = typed.chars().chain(std::iter::once(ch)).nfc().collect();
+    let candidate_len = grapheme_count(&candidate);
+    if candidate_len > grapheme_count(typed) && ends_partial(typed, target, ascii_folding) {
+        return false;
+    }
+    match (candidate.graphemes(true).next_back(), target.graphemes(true).nth(candidate_len - 1)) {
+        (Some(last), Some(target_grapheme)) => {
+            compare_graphemes(last, target_grapheme, ascii_folding) != GraphemeMatch::Wrong
+        }
+        _ => false,
+    }
+}
+
 pub struct InputHandler {
-    typed_text: String,
+    typed_words: Vec<String>,
+    current_word: usize,
     keystrokes: Vec<KeystrokeData>,
+    // Position in the text after each keystroke, see `get_position_chars`
+    positions: Vec<usize>,
     start_time: Option<Instant>,
     last_keystroke_time: Option<Instant>,
 }
@@ -22,8 +129,10 @@ pub struct InputHandler {
 impl InputHandler {
     pub fn new() -> Self {
         Self {
-            typed_text: String::new(),
+            typed_words: vec![String::new()],
+            current_word: 0,
             keystrokes: Vec::new(),
+            positions: Vec::new(),
             start_time: None,
             last_keystroke_time: None,
         }
@@ -37,56 +146,180 @@ impl InputHandler {
         }
 
         match key.code {
+            KeyCode::Char(' ') => {
+                self.handle_separator(' ', now, test);
+            }
+            KeyCode::Enter => {
+                self.handle_separator('\n', now, test);
+            }
+            KeyCode::Tab => {
+                self.handle_tab(now, test);
+            }
             KeyCode::Char(ch) => {
                 self.handle_character(ch, now, test);
             }
             KeyCode::Backspace => {
-                self.handle_backspace(now);
+                self.handle_backspace(now, test);
             }
             _ => {}
         }
+        let position = self.get_position_chars(test);
+        self.positions.resize(self.keystrokes.len(), position);
         self.last_keystroke_time = Some(now);
         Ok(())
     }
 
     fn handle_character(&mut self, ch: char, timestamp: Instant, test: &Test) {
-        let target_text = test.get_text();
-        let target_chars: Vec<char> = target_text.chars().collect();
-        let current_pos = self.typed_text.chars().count();
-        
-        // Don't allow typing beyond the target text
-        if current_pos >= target_chars.len() { 
-            return; 
+        let Some(target_word) = test.get_words().get(self.current_word) else {
+            return;
+        };
+        let target_len = grapheme_count(target_word);
+        let typed = &mut self.typed_words[self.current_word];
+
+        if grapheme_count(typed) >= target_len + MAX_EXTRA_CHARS {
+            return;
         }
-        
-        let expected_char = target_chars.get(current_pos);
-        let is_correct = expected_char == Some(&ch);
-        
-        self.typed_text.push(ch);
-        self.keystrokes.push(KeystrokeData { 
-            character: ch, 
-            timestamp, 
-            is_correct, 
+
+        let ascii_folding = test.get_ascii_folding();
+        let expected = next_expected(typed, target_word, ascii_folding);
+        let is_correct = keystroke_matches(typed, ch, target_word, ascii_folding);
+
+        if is_correct || test.get_error_policy() != ErrorPolicy::StopOnLetter {
+            // Kept composed like the target, so dead keys sent as two chars still match
+            typed.push(ch);
+            *typed = typed.nfc().collect();
+        }
+        self.keystrokes.push(KeystrokeData {
+            expected,
+            timestamp,
+            is_correct,
+            is_correction: false,
+        });
+    }
+
+    /// Handles Space and Enter. Whichever one the word's break calls for submits
+    /// the current word, leaving any untyped chars as missed; the other one is
+    /// recorded as a mistake, as is submitting an imperfect word under a stop
+    /// policy. Space inside typed indentation is just a char.
+    fn handle_separator(&mut self, separator: char, timestamp: Instant, test: &mut Test) {
+        let Some(target_word) = test.get_words().get(self.current_word) else {
+            return;
+        };
+        let typed = &self.typed_words[self.current_word];
+        let ascii_folding = test.get_ascii_folding();
+        let next_char = next_expected(typed, target_word, ascii_folding);
+
+        if separator == ' ' && next_char == Some(' ') {
+            self.handle_character(' ', timestamp, test);
+            return;
+        }
+        if typed.is_empty() {
+            return;
+        }
+
+        let word_break = match test.get_word_break(self.current_word) {
+            WordBreak::Space => ' ',
+            WordBreak::Newline { .. } => '\n',
+        };
+        let submits = separator == word_break
+            && (test.get_error_policy() == ErrorPolicy::Off || words_match(typed, target_word, ascii_folding));
+        let is_correct = submits && next_char.is_none();
+
+        self.keystrokes.push(KeystrokeData {
+            expected: next_char.or(Some(word_break)),
+            timestamp,
+            is_correct,
             is_correction: false,
         });
+        if !submits {
+            return;
+        }
+        self.current_word += 1;
+        self.typed_words.push(String::new());
+        test.ensure_words_ahead(self.current_word);
+        test.track_memory_window(self.current_word);
+    }
+
+    /// Tab fills typed indentation up to the next tab stop, or matches a literal tab.
+    fn handle_tab(&mut self, timestamp: Instant, test: &Test) {
+        let Some(target_word) = test.get_words().get(self.current_word) else {
+            return;
+        };
+        let typed = &self.typed_words[self.current_word];
+        if next_expected(typed, target_word, test.get_ascii_folding()) != Some(' ') {
+            self.handle_character('\t', timestamp, test);
+            return;
+        }
+
+        let typed_len = grapheme_count(typed);
+        let tab_width = test.get_tab_width();
+        let spaces = target_word
+            .graphemes(true)
+            .skip(typed_len)
+            .take(tab_width - typed_len % tab_width)
+            .take_while(|&g| g == " ")
+            .count();
+        for _ in 0..spaces {
+            self.handle_character(' ', timestamp, test);
+        }
     }
 
-    fn handle_backspace(&mut self, timestamp: Instant) {
-        if !self.typed_text.is_empty() {
-            let removed_char = self.typed_text.pop().unwrap();
-            self.keystrokes.push(KeystrokeData { 
-                character: removed_char, 
-                timestamp, 
+    fn handle_backspace(&mut self, timestamp: Instant, test: &Test) {
+        if self.typed_words[self.current_word].is_empty() {
+            // Only step back into the previous word if it still needs fixing.
+            if self.current_word == 0 || self.is_word_correct(self.current_word - 1, test) {
+                return;
+            }
+            self.typed_words.pop();
+            self.current_word -= 1;
+            self.keystrokes.push(KeystrokeData {
+                expected: None,
+                timestamp,
                 is_correct: true,
-                is_correction: true 
+                is_correction: true,
             });
+            return;
+        }
+
+        // A whole grapheme cluster goes at once, like a terminal line editor
+        let typed = &mut self.typed_words[self.current_word];
+        let last_start = typed.grapheme_indices(true).next_back().map_or(0, |(i, _)| i);
+        typed.truncate(last_start);
+        self.keystrokes.push(KeystrokeData {
+            expected: None,
+            timestamp,
+            is_correct: true,
+            is_correction: true,
+        });
+    }
+
+    pub fn is_word_correct(&self, index: usize, test: &Test) -> bool {
+        match (self.typed_words.get(index), test.get_words().get(index)) {
+            (Some(typed), Some(target)) => words_match(typed, target, test.get_ascii_folding()),
+            _ => false,
+        }
+    }
+
+    /// True once every word has been submitted, or the last word has been typed exactly.
+    pub fn is_finished(&self, test: &Test) -> bool {
+        if test.is_endless() {
+            return false;
+        }
+        let word_count = test.get_word_count();
+        if word_count == 0 || self.current_word >= word_count {
+            return true;
         }
+        self.current_word == word_count - 1 && self.is_word_correct(self.current_word, test)
     }
 
-    pub fn get_typed_text(&self) -> &str {
-        &self.typed_text
+    pub fn get_typed_words_input(&self) -> &[String] {
+        &self.typed_words
     }
-    
+
+    pub fn get_current_word_index(&self) -> usize {
+        self.current_word
+    }
+
     pub fn get_keystrokes(&self) -> &[KeystrokeData] {
         &self.keystrokes
     }
@@ -95,21 +328,71 @@ impl InputHandler {
         self.start_time
     }
 
-    pub fn get_progress(&self, target_text: &str) -> f64 {
-        let target_char_count = target_text.chars().count();
-        if target_char_count == 0 {
+    /// Time since the first keystroke and position in the text after every keystroke.
+    pub fn get_timeline(&self) -> Vec<(Duration, usize)> {
+        let Some(start) = self.start_time else {
+            return Vec::new();
+        };
+        self.keystrokes
+            .iter()
+            .zip(&self.positions)
+            .map(|(keystroke, &position)| (keystroke.timestamp.duration_since(start), position))
+            .collect()
+    }
+
+    pub fn get_progress(&self, test: &Test) -> f64 {
+        let word_count = test.get_word_count();
+        if word_count == 0 {
             return 1.0;
         }
-        let typed_char_count = self.typed_text.chars().count();
-        (typed_char_count as f64 / target_char_count as f64).min(1.0)
+        (self.get_typed_words() as f64 / word_count as f64).min(1.0)
     }
 
+    /// Number of words submitted so far.
     pub fn get_typed_words(&self) -> usize {
-        if self.typed_text.is_empty() {
-            0
-        } else {
-            self.typed_text.split_whitespace().count()
-        }
+        self.current_word
+    }
+
+    /// Chars of every correctly typed word plus the space that submitted it,
+    /// which is what net WPM is based on.
+    pub fn get_correct_word_chars(&self, test: &Test) -> usize {
+        let targets = test.get_words();
+        self.typed_words
+            .iter()
+            .enumerate()
+            .filter(|(i, typed)| targets.get(*i).is_some_and(|target| words_match(typed, target, test.get_ascii_folding())))
+            .map(|(i, typed)| grapheme_count(typed) + usize::from(i < self.current_word))
+            .sum()
+    }
+
+    /// How far into the text the cursor is, counting target graphemes plus one
+    /// separator per submitted word, the same units as the pace caret.
+    pub fn get_position_chars(&self, test: &Test) -> usize {
+        let words = test.get_words();
+        let submitted: usize = words.iter().take(self.current_word).map(|w| grapheme_count(w) + 1).sum();
+        let current_len = words.get(self.current_word).map_or(0, |w| grapheme_count(w));
+        submitted + grapheme_count(&self.typed_words[self.current_word]).min(current_len)
+    }
+
+    /// Chars left untyped in words that were submitted early.
+    pub fn get_missed_chars(&self, test: &Test) -> usize {
+        let targets = test.get_words();
+        self.typed_words
+            .iter()
+            .take(self.current_word)
+            .zip(targets)
+            .map(|(typed, target)| grapheme_count(target).saturating_sub(grapheme_count(typed)))
+            .sum()
+    }
+
+    /// Chars typed past the end of their target word.
+    pub fn get_extra_chars(&self, test: &Test) -> usize {
+        let targets = test.get_words();
+        self.typed_words
+            .iter()
+            .zip(targets)
+            .map(|(typed, target)| grapheme_count(typed).saturating_sub(grapheme_count(target)))
+            .sum()
     }
 
     pub fn get_live_stats(&self, elapsed: Duration) -> LiveStats {
@@ -143,29 +426,30 @@ impl InputHandler {
         }
     }
 
-    pub fn calculate_error_frequency(&self, target_text: &str) -> HashMap<char, usize> {
+    pub fn calculate_error_frequency(&self) -> HashMap<char, usize> {
         let mut frequency = HashMap::new();
-        let target_chars: Vec<char> = target_text.chars().collect();
-        let mut pos = 0;
-        
         for keystroke in &self.keystrokes {
-            if keystroke.is_correction {
-                if pos > 0 {
-                    pos -= 1;
-                }
-            } else {
-                if pos < target_chars.len() {
-                    let expected = target_chars[pos];
-                    if keystroke.character != expected {
-                        *frequency.entry(expected).or_insert(0) += 1;
-                    }
-                }
-                pos += 1;
+            if keystroke.is_correction || keystroke.is_correct {
+                continue;
+            }
+            if let Some(expected) = keystroke.expected {
+                *frequency.entry(expected).or_insert(0) += 1;
             }
         }
         frequency
     }
 
+    /// How many times each target char was attempted, right or wrong.
+    pub fn calculate_char_attempts(&self) -> HashMap<char, usize> {
+        let mut attempts = HashMap::new();
+        for keystroke in self.keystrokes.iter().filter(|k| !k.is_correction) {
+            if let Some(expected) = keystroke.expected {
+                *attempts.entry(expected).or_insert(0) += 1;
+            }
+        }
+        attempts
+    }
+
     pub fn get_speed_over_time(&self) -> Vec<(f64, f64)> {
         if self.start_time.is_none() || self.keystrokes.is_empty() {
             return vec![(0.0, 0.0)];
@@ -211,4 +495,217 @@ impl InputHandler {
             1.0
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::TestMode, config::Config};
+    use crossterm::event::KeyModifiers;
+
+    fn type_str(handler: &mut InputHandler, test: &mut Test, text: &str) {
+        for ch in text.chars() {
+            handler.handle_key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE), test).unwrap();
+        }
+    }
+
+    fn text_test(text: &str) -> Test {
+        Test::new(&TestMode::Text(text.to_string()), &Config::default()).unwrap()
+    }
+
+    fn policy_test(text: &str, error_policy: ErrorPolicy) -> Test {
+        let mut config = Config::default();
+        config.test_settings.error_policy = error_policy;
+        Test::new(&TestMode::Text(text.to_string()), &config).unwrap()
+    }
+
+    #[test]
+    fn test_stop_on_letter_rejects_wrong_chars() {
+        let mut test = policy_test("cat dog", ErrorPolicy::StopOnLetter);
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "cxa ");
+        assert_eq!(handler.get_typed_words_input()[0], "ca");
+        assert_eq!(handler.get_current_word_index(), 0);
+        assert_eq!(handler.get_keystrokes().iter().filter(|k| !k.is_correct).count(), 2);
+
+        type_str(&mut handler, &mut test, "t ");
+        assert_eq!(handler.get_current_word_index(), 1);
+    }
+
+    #[test]
+    fn test_stop_on_word_blocks_submitting_errors() {
+        let mut test = policy_test("cat dog", ErrorPolicy::StopOnWord);
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "cax ");
+        assert_eq!(handler.get_typed_words_input()[0], "cax");
+        assert_eq!(handler.get_current_word_index(), 0);
+
+        press(&mut handler, &mut test, KeyCode::Backspace);
+        type_str(&mut handler, &mut test, "t ");
+        assert_eq!(handler.get_current_word_index(), 1);
+    }
+
+    #[test]
+    fn test_grapheme_clusters_type_as_one_char() {
+        // "café" with a combining accent, then a wide CJK word
+        let mut test = text_test("cafe\u{301} 日本");
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "cafe");
+        assert_eq!(handler.get_position_chars(&test), 4);
+        assert_eq!(next_expected(&handler.get_typed_words_input()[0], "café", false), Some('\u{301}'));
+
+        type_str(&mut handler, &mut test, "\u{301} 日");
+        assert!(handler.get_keystrokes().iter().all(|k| k.is_correct));
+        assert_eq!(handler.get_correct_word_chars(&test), 5);
+        assert_eq!(handler.get_position_chars(&test), 6);
+
+        type_str(&mut handler, &mut test, "本");
+        assert!(handler.is_finished(&test));
+        assert_eq!(handler.get_missed_chars(&test), 0);
+    }
+
+    #[test]
+    fn test_composed_and_decomposed_input_match() {
+        // Decomposed target, precomposed input
+        let mut test = text_test("cafe\u{301}");
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "café");
+        assert!(handler.get_keystrokes().iter().all(|k| k.is_correct));
+        assert!(handler.is_finished(&test));
+
+        // A dead key that arrives after its letter
+        let mut test = text_test("über");
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "u\u{308}ber");
+        assert!(handler.is_finished(&test));
+    }
+
+    #[test]
+    fn test_ascii_folding() {
+        let mut config = Config::default();
+        config.test_settings.ascii_folding = true;
+        let mut test = Test::new(&TestMode::Text("café olé".to_string()), &config).unwrap();
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "cafe ole");
+        assert!(handler.get_keystrokes().iter().all(|k| k.is_correct));
+        assert!(handler.is_finished(&test));
+
+        let mut test = text_test("café olé");
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "cafe ");
+        assert!(!handler.is_word_correct(0, &test));
+    }
+
+    #[test]
+    fn test_backspace_removes_whole_grapheme() {
+        let mut test = text_test("ne\u{301}e");
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "ne\u{301}");
+        press(&mut handler, &mut test, KeyCode::Backspace);
+        assert_eq!(handler.get_typed_words_input()[0], "n");
+    }
+
+    fn code_test(auto_indent: bool) -> Test {
+        let path = std::env::temp_dir().join(format!("typing-test-code-{}.py", auto_indent));
+        std::fs::write(&path, "if ok:\n    run()").unwrap();
+        let mut config = Config::default();
+        config.test_settings.auto_indent = auto_indent;
+        let source = crate::code::CodeSource::File { path: path.clone(), language: None };
+        let test = Test::new(&TestMode::Code(source), &config).unwrap();
+        std::fs::remove_file(path).unwrap();
+        test
+    }
+
+    fn press(handler: &mut InputHandler, test: &mut Test, code: KeyCode) {
+        handler.handle_key(KeyEvent::new(code, KeyModifiers::NONE), test).unwrap();
+    }
+
+    #[test]
+    fn test_enter_ends_code_lines() {
+        let mut test = code_test(true);
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "if ok: ");
+        assert_eq!(handler.get_current_word_index(), 1);
+        assert!(!handler.get_keystrokes().last().unwrap().is_correct);
+
+        press(&mut handler, &mut test, KeyCode::Enter);
+        assert_eq!(handler.get_current_word_index(), 2);
+        type_str(&mut handler, &mut test, "run()");
+        assert!(handler.is_finished(&test));
+    }
+
+    #[test]
+    fn test_tab_types_indentation() {
+        let mut test = code_test(false);
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "if ok:");
+        press(&mut handler, &mut test, KeyCode::Enter);
+        press(&mut handler, &mut test, KeyCode::Tab);
+        assert_eq!(handler.get_typed_words_input()[2], "    ");
+        type_str(&mut handler, &mut test, "run()");
+        assert!(handler.is_finished(&test));
+    }
+
+    #[test]
+    fn test_space_skips_to_next_word() {
+        let mut test = text_test("hello world");
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "he ");
+
+        assert_eq!(handler.get_current_word_index(), 1);
+        assert_eq!(handler.get_missed_chars(&test), 3);
+        assert_eq!(handler.calculate_error_frequency().get(&'l'), Some(&1));
+    }
+
+    #[test]
+    fn test_extra_chars_are_recorded() {
+        let mut test = text_test("cat dog");
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "catss ");
+
+        assert_eq!(handler.get_typed_words_input()[0], "catss");
+        assert_eq!(handler.get_extra_chars(&test), 2);
+        assert_eq!(handler.get_correct_word_chars(&test), 0);
+    }
+
+    #[test]
+    fn test_backspace_returns_to_incorrect_word_only() {
+        let mut test = text_test("one two three");
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "one tow ");
+        let backspace = KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE);
+
+        handler.handle_key(backspace, &mut test).unwrap();
+        assert_eq!(handler.get_current_word_index(), 1);
+        handler.handle_key(backspace, &mut test).unwrap();
+        handler.handle_key(backspace, &mut test).unwrap();
+        handler.handle_key(backspace, &mut test).unwrap();
+        handler.handle_key(backspace, &mut test).unwrap();
+        assert_eq!(handler.get_current_word_index(), 1);
+        assert_eq!(handler.get_typed_words_input()[1], "");
+    }
+
+    #[test]
+    fn test_timeline_tracks_position() {
+        let mut test = text_test("ab cd");
+        let mut handler = InputHandler::new();
+        type_str(&mut handler, &mut test, "ax");
+        press(&mut handler, &mut test, KeyCode::Backspace);
+        type_str(&mut handler, &mut test, "b c");
+
+        let positions: Vec<usize> = handler.get_timeline().iter().map(|&(_, position)| position).collect();
+        assert_eq!(positions, vec![1, 2, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_word_count_finishes_on_last_word() {
+        let mut test = Test::new(&TestMode::WordCount(3), &Config::default()).unwrap();
+        let words = test.get_words().to_vec();
+        let mut handler = InputHandler::new();
+
+        type_str(&mut handler, &mut test, &format!("{} {} ", words[0], words[1]));
+        assert!(!handler.is_finished(&test));
+        type_str(&mut handler, &mut test, "x ");
+        assert!(handler.is_finished(&test));
+    }
+}
@@ -0,0 +1,347 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::TestSettings;
+use crate::input::KeystrokeData;
+
+// Older data is scaled down once a key has this many attempts, so the
+// profile follows recent sessions instead of the whole typing history.
+const PROFILE_WINDOW: f64 = 300.0;
+// Keys need this many attempts before they can count as weak.
+const MIN_ATTEMPTS: f64 = 10.0;
+// Gaps longer than this are pauses, not key latency.
+const MAX_LATENCY: Duration = Duration::from_secs(2);
+// At full strength, words with the weakest key are this many times more likely.
+const MAX_WORD_BOOST: f64 = 9.0;
+// Order letter unlock mode adds letters in, most frequent in English first.
+pub const LETTER_ORDER: &str = "etaoinshrdlcumwfgypbvkjxqz";
+pub const STARTING_LETTERS: usize = 6;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyStats {
+    pub attempts: f64,
+    pub errors: f64,
+    pub latency_total_ms: f64,
+    pub latency_samples: f64,
+}
+
+impl KeyStats {
+    pub fn error_rate(&self) -> f64 {
+        if self.attempts > 0.0 { self.errors / self.attempts } else { 0.0 }
+    }
+
+    pub fn average_latency_ms(&self) -> Option<f64> {
+        if self.latency_samples > 0.0 {
+            Some(self.latency_total_ms / self.latency_samples)
+        } else {
+            None
+        }
+    }
+
+    /// Typing speed on this key alone, from its average latency.
+    pub fn speed_wpm(&self) -> Option<f64> {
+        self.average_latency_ms().filter(|&ms| ms > 0.0).map(|ms| 60_000.0 / (ms * 5.0))
+    }
+
+    fn decay(&mut self) {
+        if self.attempts > PROFILE_WINDOW {
+            let scale = PROFILE_WINDOW / self.attempts;
+            self.attempts *= scale;
+            self.errors *= scale;
+            self.latency_total_ms *= scale;
+            self.latency_samples *= scale;
+        }
+    }
+}
+
+/// Per-key error rate and latency, accumulated across sessions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyProfile {
+    pub keys: HashMap<char, KeyStats>,
+    /// How many of `LETTER_ORDER` letter unlock mode has opened up
+    #[serde(default)]
+    pub unlocked_letters: usize,
+}
+
+impl KeyProfile {
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let profile_path = Self::get_profile_path()?;
+        if profile_path.exists() {
+            let content = fs::read_to_string(&profile_path)?;
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            Ok(KeyProfile::default())
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let profile_path = Self::get_profile_path()?;
+        if let Some(parent) = profile_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&profile_path, content)?;
+        Ok(())
+    }
+
+    fn get_profile_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let mut path = dirs::data_dir().or_else(dirs::config_dir).ok_or("Could not find data or config directory")?;
+        path.push("typing-test");
+        path.push("key_profile.json");
+        Ok(path)
+    }
+
+    pub fn record_session(&mut self, keystrokes: &[KeystrokeData]) {
+        let mut previous = None;
+        for keystroke in keystrokes {
+            let since_previous = previous.map(|time| keystroke.timestamp.duration_since(time));
+            previous = Some(keystroke.timestamp);
+
+            if keystroke.is_correction {
+                continue;
+            }
+            let Some(expected) = keystroke.expected else {
+                continue;
+            };
+
+            let stats = self.keys.entry(expected).or_default();
+            stats.attempts += 1.0;
+            if !keystroke.is_correct {
+                stats.errors += 1.0;
+            } else if let Some(latency) = since_previous.filter(|gap| *gap <= MAX_LATENCY) {
+                stats.latency_total_ms += latency.as_secs_f64() * 1000.0;
+                stats.latency_samples += 1.0;
+            }
+        }
+
+        for stats in self.keys.values_mut() {
+            stats.decay();
+        }
+    }
+
+    pub fn get_unlocked_letters(&self) -> Vec<char> {
+        let count = self.unlocked_letters.clamp(STARTING_LETTERS, LETTER_ORDER.len());
+        LETTER_ORDER.chars().take(count).collect()
+    }
+
+    /// Unlocked letters that haven't reached the target yet, furthest behind first.
+    pub fn letters_below_target(&self, target: &UnlockTarget) -> Vec<char> {
+        let mut below: Vec<(char, f64)> = self
+            .get_unlocked_letters()
+            .into_iter()
+            .filter_map(|ch| {
+                let progress = self.keys.get(&ch).map_or(0.0, |stats| target.progress(stats));
+                (progress < 1.0).then_some((ch, progress))
+            })
+            .collect();
+        below.sort_by(|a, b| a.1.total_cmp(&b.1));
+        below.into_iter().map(|(ch, _)| ch).collect()
+    }
+
+    /// The letter drills should lean on: the one furthest from the target, or
+    /// the newest once they are all there.
+    pub fn focus_letter(&self, target: &UnlockTarget) -> Option<char> {
+        self.letters_below_target(target).first().copied().or_else(|| self.get_unlocked_letters().last().copied())
+    }
+
+    /// Unlocks the next letter if every unlocked one meets the target, returning it.
+    pub fn unlock_next_letter(&mut self, target: &UnlockTarget) -> Option<char> {
+        let unlocked = self.get_unlocked_letters();
+        if !self.letters_below_target(target).is_empty() {
+            return None;
+        }
+        let next = LETTER_ORDER.chars().nth(unlocked.len())?;
+        self.unlocked_letters = unlocked.len() + 1;
+        Some(next)
+    }
+
+    /// Weakness of each sufficiently practised key from 0 (best) to 1 (worst),
+    /// blending its error rate and its average latency.
+    pub fn key_weakness(&self) -> HashMap<char, f64> {
+        let eligible: Vec<(char, f64, Option<f64>)> = self
+            .keys
+            .iter()
+            .filter(|(ch, stats)| !ch.is_whitespace() && stats.attempts >= MIN_ATTEMPTS)
+            .map(|(&ch, stats)| (ch, stats.error_rate(), stats.average_latency_ms()))
+            .collect();
+
+        let max_error_rate = eligible.iter().map(|k| k.1).fold(0.0, f64::max);
+        let latencies: Vec<f64> = eligible.iter().filter_map(|k| k.2).collect();
+        let min_latency = latencies.iter().copied().fold(f64::INFINITY, f64::min);
+        let max_latency = latencies.iter().copied().fold(0.0, f64::max);
+
+        eligible
+            .into_iter()
+            .map(|(ch, error_rate, latency)| {
+                let error_part = if max_error_rate > 0.0 { error_rate / max_error_rate } else { 0.0 };
+                let latency_part = match latency {
+                    Some(latency) if max_latency > min_latency => (latency - min_latency) / (max_latency - min_latency),
+                    _ => 0.0,
+                };
+                (ch, (error_part + latency_part) / 2.0)
+            })
+            .collect()
+    }
+
+    /// Word weighting for the random word generators, or `None` when adapting is
+    /// switched off or there isn't enough data yet.
+    pub fn word_bias(&self, strength: f64) -> Option<WordBias> {
+        let strength = strength.clamp(0.0, 1.0);
+        if strength == 0.0 {
+            return None;
+        }
+        let weakness = self.key_weakness();
+        if weakness.values().all(|&w| w == 0.0) {
+            return None;
+        }
+        Some(WordBias { weakness, strength })
+    }
+}
+
+/// Speed and accuracy each letter needs before letter unlock mode moves on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnlockTarget {
+    pub wpm: f64,
+    /// Percent
+    pub accuracy: f64,
+}
+
+impl UnlockTarget {
+    pub fn from_settings(settings: &TestSettings) -> Self {
+        Self { wpm: settings.unlock_target_wpm, accuracy: settings.unlock_target_accuracy }
+    }
+
+    /// How close a key is to the target, where 1 means it is there.
+    fn progress(&self, stats: &KeyStats) -> f64 {
+        if stats.attempts < MIN_ATTEMPTS {
+            return 0.0;
+        }
+        let speed = match stats.speed_wpm() {
+            Some(wpm) if self.wpm > 0.0 => (wpm / self.wpm).min(1.0),
+            Some(_) => 1.0,
+            None => 0.0,
+        };
+        let accuracy = (1.0 - stats.error_rate()) * 100.0;
+        if accuracy < self.accuracy { speed.min(0.99) } else { speed }
+    }
+}
+
+/// How a letter unlock drill moved the unlocked set along.
+#[derive(Debug, Clone)]
+pub struct UnlockReport {
+    pub letters: Vec<char>,
+    pub new_letter: Option<char>,
+    pub below_target: Vec<char>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WordBias {
+    weakness: HashMap<char, f64>,
+    strength: f64,
+}
+
+impl WordBias {
+    /// Relative chance of picking `word`, driven by its weakest key.
+    pub fn word_weight(&self, word: &str) -> f64 {
+        let worst = word
+            .chars()
+            .filter_map(|ch| self.weakness.get(&ch.to_ascii_lowercase()))
+            .copied()
+            .fold(0.0, f64::max);
+        1.0 + self.strength * MAX_WORD_BOOST * worst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn keystrokes(pattern: &[(char, bool, u64)]) -> Vec<KeystrokeData> {
+        let start = Instant::now();
+        pattern
+            .iter()
+            .map(|&(expected, is_correct, at_ms)| KeystrokeData {
+                expected: Some(expected),
+                timestamp: start + Duration::from_millis(at_ms),
+                is_correct,
+                is_correction: false,
+            })
+            .collect()
+    }
+
+    fn practised_profile() -> KeyProfile {
+        let mut pattern = Vec::new();
+        let mut time = 0;
+        for i in 0..20 {
+            time += 100;
+            pattern.push(('a', true, time));
+            time += 400;
+            pattern.push(('z', i % 2 == 0, time));
+        }
+        let mut profile = KeyProfile::default();
+        profile.record_session(&keystrokes(&pattern));
+        profile
+    }
+
+    #[test]
+    fn test_record_session() {
+        let profile = practised_profile();
+        let a = &profile.keys[&'a'];
+        let z = &profile.keys[&'z'];
+        assert_eq!(a.attempts, 20.0);
+        assert_eq!(z.error_rate(), 0.5);
+        assert!(z.average_latency_ms().unwrap() > a.average_latency_ms().unwrap());
+    }
+
+    #[test]
+    fn test_word_bias_prefers_weak_keys() {
+        let profile = practised_profile();
+        let weakness = profile.key_weakness();
+        assert_eq!(weakness[&'z'], 1.0);
+        assert_eq!(weakness[&'a'], 0.0);
+
+        assert!(profile.word_bias(0.0).is_none());
+        let bias = profile.word_bias(1.0).unwrap();
+        assert!(bias.word_weight("zoo") > bias.word_weight("cat"));
+        assert_eq!(bias.word_weight("cat"), 1.0);
+    }
+
+    #[test]
+    fn test_letter_unlocking() {
+        let mut profile = KeyProfile::default();
+        let starting: Vec<char> = LETTER_ORDER.chars().take(STARTING_LETTERS).collect();
+        assert_eq!(profile.get_unlocked_letters(), starting);
+
+        // 100ms per key is 120 WPM
+        let target = UnlockTarget { wpm: 60.0, accuracy: 90.0 };
+        let mut pattern = Vec::new();
+        for i in 0..MIN_ATTEMPTS as u64 {
+            for (j, &ch) in starting.iter().enumerate() {
+                pattern.push((ch, true, (i * starting.len() as u64 + j as u64 + 1) * 100));
+            }
+        }
+        // The first letter still lacks attempts, so nothing unlocks yet
+        pattern.retain(|&(ch, _, at)| ch != 'e' || at < 1000);
+        profile.record_session(&keystrokes(&pattern));
+        assert_eq!(profile.focus_letter(&target), Some('e'));
+        assert_eq!(profile.unlock_next_letter(&target), None);
+
+        profile.keys.get_mut(&'e').unwrap().attempts = MIN_ATTEMPTS;
+        assert!(profile.letters_below_target(&target).is_empty());
+        assert_eq!(profile.unlock_next_letter(&target), Some('s'));
+        assert_eq!(profile.get_unlocked_letters().len(), STARTING_LETTERS + 1);
+        assert_eq!(profile.focus_letter(&target), Some('s'));
+    }
+
+    #[test]
+    fn test_decay_keeps_recent_window() {
+        let mut stats = KeyStats { attempts: 600.0, errors: 60.0, latency_total_ms: 0.0, latency_samples: 0.0 };
+        stats.decay();
+        assert_eq!(stats.attempts, PROFILE_WINDOW);
+        assert_eq!(stats.error_rate(), 0.1);
+    }
+}
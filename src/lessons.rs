@@ -0,0 +1,191 @@
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::stats::Stats;
+use crate::utils;
+
+pub const LESSON_DRILL_WORDS: usize = 30;
+
+/// One step of the course. Each lesson adds `new_keys` to the keys unlocked
+/// by the lessons before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lesson {
+    pub name: &'static str,
+    pub new_keys: &'static str,
+    pub min_wpm: f64,
+    /// Percent
+    pub min_accuracy: f64,
+}
+
+pub const LESSONS: &[Lesson] = &[
+    Lesson { name: "Home Row", new_keys: "asdfjkl;", min_wpm: 15.0, min_accuracy: 95.0 },
+    Lesson { name: "Home Row Reach", new_keys: "gh", min_wpm: 18.0, min_accuracy: 95.0 },
+    Lesson { name: "Top Row", new_keys: "qwertyuiop", min_wpm: 20.0, min_accuracy: 94.0 },
+    Lesson { name: "Bottom Row", new_keys: "zxcvbnm,.", min_wpm: 22.0, min_accuracy: 94.0 },
+    Lesson { name: "Numbers", new_keys: "1234567890", min_wpm: 18.0, min_accuracy: 92.0 },
+    Lesson { name: "Symbols", new_keys: "-=[]'/!?()", min_wpm: 15.0, min_accuracy: 90.0 },
+];
+
+impl Lesson {
+    pub fn get(index: usize) -> Option<&'static Lesson> {
+        LESSONS.get(index)
+    }
+
+    /// Every key this lesson may use, including ones from earlier lessons.
+    pub fn unlocked_keys(index: usize) -> Vec<char> {
+        LESSONS.iter().take(index + 1).flat_map(|lesson| lesson.new_keys.chars()).collect()
+    }
+
+    pub fn generate_drill(index: usize, rng: &mut impl Rng) -> String {
+        let new_keys: Vec<char> = LESSONS[index].new_keys.chars().collect();
+        utils::generate_restricted_practice(&Self::unlocked_keys(index), &new_keys, LESSON_DRILL_WORDS, rng)
+    }
+
+    pub fn is_passed_by(&self, stats: &Stats) -> bool {
+        !stats.failed && stats.wpm >= self.min_wpm && stats.accuracy * 100.0 >= self.min_accuracy
+    }
+
+    pub fn get_requirements(&self) -> String {
+        format!("{:.0} WPM, {:.0}% accuracy", self.min_wpm, self.min_accuracy)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LessonRecord {
+    pub best_wpm: f64,
+    pub best_accuracy: f64,
+    pub attempts: u32,
+    pub passed_at: Option<DateTime<Utc>>,
+}
+
+/// Course progress, keyed by lesson name so reordering lessons keeps it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LessonProgress {
+    records: HashMap<String, LessonRecord>,
+}
+
+impl LessonProgress {
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let progress_path = Self::get_progress_path()?;
+        if progress_path.exists() {
+            let content = fs::read_to_string(&progress_path)?;
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            Ok(LessonProgress::default())
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let progress_path = Self::get_progress_path()?;
+        if let Some(parent) = progress_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&progress_path, content)?;
+        Ok(())
+    }
+
+    fn get_progress_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let mut path = dirs::data_dir().or_else(dirs::config_dir).ok_or("Could not find data or config directory")?;
+        path.push("typing-test");
+        path.push("lessons.json");
+        Ok(path)
+    }
+
+    pub fn get_record(&self, index: usize) -> Option<&LessonRecord> {
+        self.records.get(LESSONS.get(index)?.name)
+    }
+
+    pub fn is_passed(&self, index: usize) -> bool {
+        self.get_record(index).is_some_and(|record| record.passed_at.is_some())
+    }
+
+    /// The first lesson is always open; the rest open once the previous one is passed.
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        index < LESSONS.len() && (index == 0 || self.is_passed(index - 1))
+    }
+
+    /// Index of the lesson to work on next: the first unpassed one, or the last.
+    pub fn current_lesson(&self) -> usize {
+        (0..LESSONS.len()).find(|&index| !self.is_passed(index)).unwrap_or(LESSONS.len() - 1)
+    }
+
+    /// Records an attempt and returns whether it passed the lesson.
+    pub fn record_attempt(&mut self, index: usize, stats: &Stats) -> bool {
+        let lesson = &LESSONS[index];
+        let passed = lesson.is_passed_by(stats);
+        let record = self.records.entry(lesson.name.to_string()).or_insert(LessonRecord {
+            best_wpm: 0.0,
+            best_accuracy: 0.0,
+            attempts: 0,
+            passed_at: None,
+        });
+        record.attempts += 1;
+        if !stats.failed {
+            record.best_wpm = record.best_wpm.max(stats.wpm);
+            record.best_accuracy = record.best_accuracy.max(stats.accuracy);
+        }
+        if passed && record.passed_at.is_none() {
+            record.passed_at = Some(stats.timestamp);
+        }
+        passed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(wpm: f64, accuracy: f64) -> Stats {
+        Stats {
+            test_mode: "Lesson 1: Home Row".to_string(),
+            wpm,
+            raw_wpm: wpm,
+            accuracy,
+            ..Stats::sample()
+        }
+    }
+
+    #[test]
+    fn test_unlocked_keys_accumulate() {
+        let keys = Lesson::unlocked_keys(1);
+        assert!(keys.contains(&'a') && keys.contains(&'g'));
+        assert!(!keys.contains(&'q'));
+        let drill = Lesson::generate_drill(0, &mut rand::thread_rng());
+        assert_eq!(drill.split_whitespace().count(), LESSON_DRILL_WORDS);
+        assert!(drill.chars().all(|c| c == ' ' || LESSONS[0].new_keys.contains(c)));
+    }
+
+    #[test]
+    fn test_drills_use_new_keys() {
+        for (index, lesson) in LESSONS.iter().enumerate() {
+            let drill = Lesson::generate_drill(index, &mut rand::thread_rng());
+            assert!(drill.chars().any(|c| lesson.new_keys.contains(c)), "{}: {}", lesson.name, drill);
+        }
+        let bottom_row = Lesson::generate_drill(3, &mut rand::thread_rng());
+        assert!(bottom_row.contains(',') || bottom_row.contains('.'));
+        let numbers = Lesson::generate_drill(4, &mut rand::thread_rng());
+        assert!(numbers.split(' ').filter(|word| word.chars().any(|c| c.is_ascii_digit())).count() >= LESSON_DRILL_WORDS / 3);
+    }
+
+    #[test]
+    fn test_lessons_unlock_in_order() {
+        let mut progress = LessonProgress::default();
+        assert!(progress.is_unlocked(0));
+        assert!(!progress.is_unlocked(1));
+        assert_eq!(progress.current_lesson(), 0);
+
+        assert!(!progress.record_attempt(0, &stats(LESSONS[0].min_wpm - 1.0, 1.0)));
+        assert!(!progress.record_attempt(0, &stats(LESSONS[0].min_wpm, 0.5)));
+        assert!(!progress.is_unlocked(1));
+
+        assert!(progress.record_attempt(0, &stats(LESSONS[0].min_wpm, 1.0)));
+        assert!(progress.is_unlocked(1));
+        assert_eq!(progress.current_lesson(), 1);
+        assert_eq!(progress.get_record(0).unwrap().attempts, 3);
+    }
+}
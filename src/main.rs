@@ -9,25 +9,57 @@ use ratatui::{
     Terminal,
 };
 use std::io;
+use std::time::{Duration, Instant};
 
 mod app;
+mod cli;
+mod code;
 mod config;
+mod daily;
+mod file_picker;
+mod ghost;
+mod highlight;
 mod history;
 mod input;
+mod key_profile;
+mod lessons;
+mod ngrams;
+mod pace;
+mod quotes;
 mod stats;
 mod test;
+mod transforms;
 mod ui;
 mod utils;
+mod wordlists;
 
 use app::App;
+use cli::{CliArgs, TextInput};
 
 fn main() -> Result<()> {
+    let args = CliArgs::parse(std::env::args().skip(1))?;
+    if args.show_help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    let mut app = App::new()?; // Fixed: App::new() returns Result
+    // Piped text has to be read before raw mode takes over the terminal
+    match &args.text_input {
+        Some(TextInput::Path(path)) => app.load_practice_file(path, args.language)?,
+        Some(TextInput::Stdin) => {
+            let text = cli::read_stdin(&app.config.test_settings.text_load_options())?;
+            app.set_custom_text(text);
+        }
+        None => {}
+    }
+    app.seed = args.seed;
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-    let app = App::new()?; // Fixed: App::new() returns Result
     let res = run_app(&mut terminal, app); // Fixed: pass app directly
     disable_raw_mode()?;
     execute!(
@@ -39,11 +71,19 @@ fn main() -> Result<()> {
     res
 }
 
+const TICK_RATE: Duration = Duration::from_millis(100);
+
 fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
+    let mut last_tick = Instant::now();
     loop {
         terminal.draw(|f| ui::draw(f, &mut app))?;
 
-        if let Event::Key(key) = event::read()? {
+        // Wait for input only until the next tick is due so timed tests and
+        // other time-driven widgets keep updating while nobody is typing.
+        let timeout = TICK_RATE.saturating_sub(last_tick.elapsed());
+        if event::poll(timeout)?
+            && let Event::Key(key) = event::read()?
+        {
             if key.code == KeyCode::Char('q') && app.can_quit() {
                 return Ok(());
             }
@@ -52,5 +92,10 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
                 return Ok(());
             }
         }
+
+        if last_tick.elapsed() >= TICK_RATE {
+            app.on_tick()?;
+            last_tick = Instant::now();
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::input::KeystrokeData;
+
+// N-grams seen fewer times than this in a test are too noisy to rank.
+const MIN_NGRAM_ATTEMPTS: u32 = 3;
+// Gaps longer than this are pauses, not part of the n-gram.
+const MAX_GAP: Duration = Duration::from_secs(2);
+// An error counts as this many times the average transition time when ranking.
+const ERROR_PENALTY: f64 = 4.0;
+
+#[derive(Debug, Clone, Default)]
+struct NgramStats {
+    attempts: u32,
+    errors: u32,
+    latency_total_ms: f64,
+    latency_samples: u32,
+}
+
+/// How one bigram or trigram went during a test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NgramScore {
+    pub ngram: String,
+    pub attempts: u32,
+    pub error_rate: f64,
+    /// Average time per key transition inside the n-gram, over clean attempts
+    pub latency_ms: Option<f64>,
+}
+
+impl NgramScore {
+    pub fn get_summary(&self) -> String {
+        let latency = match self.latency_ms {
+            Some(ms) => format!("{:.0}ms", ms),
+            None => "-".to_string(),
+        };
+        format!("{}: {}, {:.0}% errors", self.ngram, latency, self.error_rate * 100.0)
+    }
+
+    fn cost(&self, average_latency_ms: f64) -> f64 {
+        self.latency_ms.unwrap_or(average_latency_ms) + ERROR_PENALTY * average_latency_ms * self.error_rate
+    }
+}
+
+/// Bigrams and trigrams within words, ranked slowest and most error-prone first.
+pub fn worst_ngrams(keystrokes: &[KeystrokeData], count: usize) -> Vec<NgramScore> {
+    let attempts: Vec<&KeystrokeData> = keystrokes
+        .iter()
+        .filter(|k| !k.is_correction && k.expected.is_some())
+        .collect();
+
+    let mut stats: HashMap<String, NgramStats> = HashMap::new();
+    for n in 2..=3 {
+        for window in attempts.windows(n) {
+            let expected: Vec<char> = window.iter().filter_map(|k| k.expected).collect();
+            if expected.iter().any(|ch| ch.is_whitespace()) {
+                continue;
+            }
+            let span = window[n - 1].timestamp.duration_since(window[0].timestamp);
+            if span > MAX_GAP * (n as u32 - 1) {
+                continue;
+            }
+
+            let ngram: String = expected.iter().flat_map(|ch| ch.to_lowercase()).collect();
+            let entry = stats.entry(ngram).or_default();
+            entry.attempts += 1;
+            if window.iter().all(|k| k.is_correct) {
+                entry.latency_total_ms += span.as_secs_f64() * 1000.0 / (n - 1) as f64;
+                entry.latency_samples += 1;
+            } else {
+                entry.errors += 1;
+            }
+        }
+    }
+
+    let mut scores: Vec<NgramScore> = stats
+        .into_iter()
+        .filter(|(_, s)| s.attempts >= MIN_NGRAM_ATTEMPTS)
+        .map(|(ngram, s)| NgramScore {
+            ngram,
+            attempts: s.attempts,
+            error_rate: s.errors as f64 / s.attempts as f64,
+            latency_ms: (s.latency_samples > 0).then(|| s.latency_total_ms / s.latency_samples as f64),
+        })
+        .collect();
+
+    let latencies: Vec<f64> = scores.iter().filter_map(|s| s.latency_ms).collect();
+    let average_latency_ms = if latencies.is_empty() { 0.0 } else { latencies.iter().sum::<f64>() / latencies.len() as f64 };
+    scores.sort_by(|a, b| {
+        b.cost(average_latency_ms)
+            .total_cmp(&a.cost(average_latency_ms))
+            .then_with(|| a.ngram.cmp(&b.ngram))
+    });
+    scores.truncate(count);
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn type_text(text: &str, slow_pair: (char, char), typo: char) -> Vec<KeystrokeData> {
+        let start = Instant::now();
+        let mut time = 0;
+        let mut previous = ' ';
+        text.chars()
+            .map(|expected| {
+                time += if (previous, expected) == slow_pair { 500 } else { 100 };
+                previous = expected;
+                KeystrokeData {
+                    expected: Some(expected),
+                    timestamp: start + Duration::from_millis(time),
+                    is_correct: expected != typo,
+                    is_correction: false,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_slow_ngram_ranks_first() {
+        let keystrokes = type_text("then this that those", ('t', 'h'), '!');
+        let worst = worst_ngrams(&keystrokes, 3);
+        assert_eq!(worst[0].ngram, "th");
+        assert_eq!(worst[0].attempts, 4);
+        assert_eq!(worst[0].latency_ms, Some(500.0));
+        // Spaces never join words into one n-gram
+        assert!(worst_ngrams(&keystrokes, 100).iter().all(|s| !s.ngram.contains(' ')));
+    }
+
+    #[test]
+    fn test_errors_count_against_ngram() {
+        let keystrokes = type_text("ckab ckab ckab abab", ('x', 'x'), 'c');
+        let worst = worst_ngrams(&keystrokes, 1);
+        assert_eq!(worst[0].error_rate, 1.0);
+        assert!(worst[0].ngram.contains('c'));
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::ghost::GhostRun;
+use crate::utils::grapheme_count;
+
+/// What speed the pace caret runs at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PaceTarget {
+    #[default]
+    Off,
+    /// `pace_wpm` from the config
+    Fixed,
+    /// Average net WPM of earlier runs in the same mode
+    Average,
+    /// Best net WPM of earlier runs in the same mode
+    PersonalBest,
+}
+
+impl PaceTarget {
+    pub fn all() -> Vec<PaceTarget> {
+        vec![PaceTarget::Off, PaceTarget::Fixed, PaceTarget::Average, PaceTarget::PersonalBest]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PaceTarget::Off => "Off",
+            PaceTarget::Fixed => "Fixed WPM",
+            PaceTarget::Average => "Average",
+            PaceTarget::PersonalBest => "Personal Best",
+        }
+    }
+}
+
+/// A second cursor moving through the text, either at a steady speed or by
+/// replaying a recorded run. Positions are in graphemes of target text with one
+/// separator counted after every word.
+#[derive(Debug, Clone)]
+pub enum PaceCaret {
+    Steady { wpm: f64 },
+    Ghost(GhostRun),
+}
+
+impl PaceCaret {
+    pub fn new(wpm: f64) -> Option<Self> {
+        (wpm > 0.0).then_some(PaceCaret::Steady { wpm })
+    }
+
+    pub fn get_wpm(&self) -> f64 {
+        match self {
+            PaceCaret::Steady { wpm } => *wpm,
+            PaceCaret::Ghost(run) => run.wpm,
+        }
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PaceCaret::Steady { .. } => "Pace",
+            PaceCaret::Ghost(_) => "Ghost",
+        }
+    }
+
+    pub fn chars_at(&self, elapsed: Duration) -> usize {
+        match self {
+            PaceCaret::Steady { wpm } => (wpm * 5.0 * elapsed.as_secs_f64() / 60.0) as usize,
+            PaceCaret::Ghost(run) => run.position_at(elapsed),
+        }
+    }
+}
+
+/// Finds the grapheme `chars` into `words` as a word index and an offset into
+/// that word, where an offset equal to the word's length is its separator.
+pub fn locate(words: &[String], chars: usize) -> Option<(usize, usize)> {
+    let mut remaining = chars;
+    for (index, word) in words.iter().enumerate() {
+        let len = grapheme_count(word);
+        if remaining <= len {
+            return Some((index, remaining));
+        }
+        remaining -= len + 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pace_caret_speed() {
+        let caret = PaceCaret::new(60.0).unwrap();
+        assert_eq!(caret.chars_at(Duration::from_secs(60)), 300);
+        assert_eq!(caret.chars_at(Duration::from_secs(1)), 5);
+        assert!(PaceCaret::new(0.0).is_none());
+    }
+
+    #[test]
+    fn test_locate() {
+        let words: Vec<String> = ["one", "two"].iter().map(|w| w.to_string()).collect();
+        assert_eq!(locate(&words, 0), Some((0, 0)));
+        assert_eq!(locate(&words, 3), Some((0, 3)));
+        assert_eq!(locate(&words, 4), Some((1, 0)));
+        assert_eq!(locate(&words, 7), Some((1, 3)));
+        assert_eq!(locate(&words, 8), None);
+    }
+}
@@ -0,0 +1,141 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::test::SAMPLE_TEXTS;
+use crate::utils;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub text: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuoteLength {
+    Short,
+    Medium,
+    Long,
+    Thicc,
+}
+
+impl QuoteLength {
+    pub fn all() -> [QuoteLength; 4] {
+        [QuoteLength::Short, QuoteLength::Medium, QuoteLength::Long, QuoteLength::Thicc]
+    }
+
+    pub fn for_text(text: &str) -> Self {
+        match utils::grapheme_count(text) {
+            0..=100 => QuoteLength::Short,
+            101..=300 => QuoteLength::Medium,
+            301..=600 => QuoteLength::Long,
+            _ => QuoteLength::Thicc,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            QuoteLength::Short => "Short",
+            QuoteLength::Medium => "Medium",
+            QuoteLength::Long => "Long",
+            QuoteLength::Thicc => "Thicc",
+        }
+    }
+}
+
+impl Quote {
+    pub fn length(&self) -> QuoteLength {
+        QuoteLength::for_text(&self.text)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct QuoteLibrary {
+    quotes: Vec<Quote>,
+}
+
+impl QuoteLibrary {
+    /// Bundled quotes plus any `*.json` quote files in the data directory's `quotes/` folder.
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut library = Self::bundled();
+        let quotes_dir = Self::get_quotes_dir()?;
+        if quotes_dir.is_dir() {
+            let mut paths: Vec<PathBuf> = fs::read_dir(&quotes_dir)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect();
+            paths.sort();
+            for path in paths {
+                let content = fs::read_to_string(&path)?;
+                library.add_from_json(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+        }
+        Ok(library)
+    }
+
+    pub fn bundled() -> Self {
+        let quotes = SAMPLE_TEXTS
+            .iter()
+            .map(|(text, source)| Quote { text: text.to_string(), source: source.to_string() })
+            .collect();
+        Self { quotes }
+    }
+
+    /// Adds quotes from a JSON array of `{ "text": ..., "source": ... }` objects.
+    pub fn add_from_json(&mut self, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let quotes: Vec<Quote> = serde_json::from_str(content)?;
+        self.quotes.extend(quotes.into_iter().filter(|q| !q.text.trim().is_empty()));
+        Ok(())
+    }
+
+    pub fn get_quotes(&self, length: QuoteLength) -> Vec<&Quote> {
+        self.quotes.iter().filter(|q| q.length() == length).collect()
+    }
+
+    pub fn random(&self, length: QuoteLength, rng: &mut impl Rng) -> Option<&Quote> {
+        self.get_quotes(length).choose(rng).copied()
+    }
+
+    fn get_quotes_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let mut path = dirs::data_dir().or_else(dirs::config_dir).ok_or("Could not find data or config directory")?;
+        path.push("typing-test");
+        path.push("quotes");
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_length_buckets() {
+        assert_eq!(QuoteLength::for_text("short one"), QuoteLength::Short);
+        assert_eq!(QuoteLength::for_text(&"a".repeat(200)), QuoteLength::Medium);
+        assert_eq!(QuoteLength::for_text(&"a".repeat(450)), QuoteLength::Long);
+        assert_eq!(QuoteLength::for_text(&"a".repeat(601)), QuoteLength::Thicc);
+    }
+
+    #[test]
+    fn test_bundled_quotes_cover_every_length() {
+        let library = QuoteLibrary::bundled();
+        for length in QuoteLength::all() {
+            let quote = library.random(length, &mut rand::thread_rng()).unwrap();
+            assert_eq!(quote.length(), length);
+            assert!(!quote.source.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_add_from_json() {
+        let mut library = QuoteLibrary::default();
+        library
+            .add_from_json(r#"[{"text": "Ship it.", "source": "Team motto"}, {"text": "  ", "source": "Empty"}]"#)
+            .unwrap();
+        let short = library.get_quotes(QuoteLength::Short);
+        assert_eq!(short.len(), 1);
+        assert_eq!(short[0].source, "Team motto");
+    }
+}
@@ -1,9 +1,9 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
 
-use crate::{input::InputHandler, test::Test};
+use crate::{input::{ErrorPolicy, InputHandler}, test::Test, transforms::TextTransforms, utils::WordSource};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Stats {
@@ -15,10 +15,36 @@ pub struct Stats {
     pub error_count: usize,
     pub correct_chars: usize,
     pub total_chars: usize,
+    #[serde(default)]
+    pub missed_chars: usize,
+    #[serde(default)]
+    pub extra_chars: usize,
     pub test_duration: Duration,
     pub error_frequency: HashMap<char, usize>,
+    #[serde(default)]
+    pub char_attempts: HashMap<char, usize>,
     pub speed_over_time: Vec<(f64, f64)>,
     pub consistency_score: f64,
+    #[serde(default)]
+    pub quote_source: Option<String>,
+    #[serde(default)]
+    pub word_source: Option<WordSource>,
+    /// Punctuation, capitals and numbers added to generated words
+    #[serde(default)]
+    pub transforms: TextTransforms,
+    /// Seed that regenerates the text with `--seed`, when the text comes from the seed alone
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Day of the daily challenge, set only on that day's official attempt
+    #[serde(default)]
+    pub daily: Option<NaiveDate>,
+    #[serde(default)]
+    pub error_policy: ErrorPolicy,
+    /// Ended early by sudden death or a minimum accuracy/WPM rule
+    #[serde(default)]
+    pub failed: bool,
+    #[serde(default)]
+    pub fail_reason: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -45,30 +71,43 @@ impl Stats {
             1.0
         };
 
+        // Net WPM only credits fully correct words, matching Monkeytype-style tools.
+        let correct_word_chars = input_handler.get_correct_word_chars(test);
         let (wpm, raw_wpm) = if elapsed_minutes > 0.0 {
             let raw_wpm = (total_chars as f64 / 5.0) / elapsed_minutes;
-            let net_wpm = (correct_chars as f64 / 5.0) / elapsed_minutes;
+            let net_wpm = (correct_word_chars as f64 / 5.0) / elapsed_minutes;
             (net_wpm, raw_wpm)
         } else {
             (0.0, 0.0)
         };
 
-        let error_frequency = input_handler.calculate_error_frequency(test.get_text());
+        let error_frequency = input_handler.calculate_error_frequency();
         let speed_over_time = input_handler.get_speed_over_time();
         let consistency_score = input_handler.get_consistency_score();
         Stats {
             timestamp: now,
-            test_mode: test.get_mode().display_name(),
+            test_mode: test.get_mode_name(),
             wpm,
             raw_wpm,
             accuracy,
             error_count,
             correct_chars,
             total_chars,
+            missed_chars: input_handler.get_missed_chars(test),
+            extra_chars: input_handler.get_extra_chars(test),
             test_duration,
             error_frequency,
+            char_attempts: input_handler.calculate_char_attempts(),
             speed_over_time,
             consistency_score,
+            quote_source: test.get_source().map(str::to_string),
+            word_source: test.get_word_source().cloned(),
+            transforms: test.get_transforms(),
+            seed: test.get_seed(),
+            daily: None,
+            error_policy: test.get_error_policy(),
+            failed: false,
+            fail_reason: None,
         }
     }
 
@@ -145,6 +184,27 @@ impl Stats {
         errors.into_iter().take(10).collect()
     }
 
+    pub fn get_error_rate(&self, ch: char) -> Option<f64> {
+        let attempts = *self.char_attempts.get(&ch)?;
+        if attempts == 0 {
+            return None;
+        }
+        let errors = self.error_frequency.get(&ch).copied().unwrap_or(0);
+        Some(errors as f64 / attempts as f64)
+    }
+
+    /// Compares this run's error rate on each key against a baseline rate.
+    pub fn compare_key_error_rates(&self, baseline: &[(char, f64)]) -> Vec<KeyImprovement> {
+        baseline
+            .iter()
+            .map(|&(character, before)| KeyImprovement {
+                character,
+                before,
+                after: self.get_error_rate(character),
+            })
+            .collect()
+    }
+
     pub fn estimate_time_to_goal(&self, target_wpm: f64) -> Option<String> {
         if self.wpm >= target_wpm {
             return Some("Goal already achieved!".to_string());
@@ -164,6 +224,31 @@ impl Stats {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct KeyImprovement {
+    pub character: char,
+    pub before: f64,
+    pub after: Option<f64>,
+}
+
+impl KeyImprovement {
+    pub fn get_summary(&self) -> String {
+        match self.after {
+            Some(after) => {
+                let verdict = if after < self.before {
+                    "improved"
+                } else if after > self.before {
+                    "worse"
+                } else {
+                    "unchanged"
+                };
+                format!("'{}': {:.0}% -> {:.0}% ({})", self.character, self.before * 100.0, after * 100.0, verdict)
+            }
+            None => format!("'{}': {:.0}% -> not typed", self.character, self.before * 100.0),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ImprovementStats {
     pub wpm_change: f64,
@@ -206,24 +291,53 @@ impl ImprovementStats {
     }
 }
 
+#[cfg(test)]
+impl Stats {
+    /// A one-minute result at 40 WPM and 90% accuracy, for tests to build on
+    /// with `..Stats::sample()`.
+    pub fn sample() -> Self {
+        Stats {
+            timestamp: Utc::now(),
+            test_mode: "Test".to_string(),
+            wpm: 40.0,
+            raw_wpm: 45.0,
+            accuracy: 0.90,
+            error_count: 10,
+            correct_chars: 90,
+            total_chars: 100,
+            missed_chars: 0,
+            extra_chars: 0,
+            test_duration: Duration::from_secs(60),
+            error_frequency: HashMap::new(),
+            char_attempts: HashMap::new(),
+            speed_over_time: vec![(0.0, 0.0)],
+            consistency_score: 0.7,
+            quote_source: None,
+            word_source: None,
+            transforms: TextTransforms::default(),
+            seed: None,
+            daily: None,
+            error_policy: ErrorPolicy::Off,
+            failed: false,
+            fail_reason: None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
     fn test_stats_calculation() {
         let stats = Stats {
-            timestamp: Utc::now(),
-            test_mode: "Test".to_string(),
             wpm: 50.0,
             raw_wpm: 55.0,
             accuracy: 0.95,
             error_count: 5,
             correct_chars: 95,
-            total_chars: 100,
-            test_duration: Duration::from_secs(60),
-            error_frequency: HashMap::new(),
             speed_over_time: vec![(0.0, 0.0), (60.0, 50.0)],
             consistency_score: 0.8,
+            ..Stats::sample()
         };
         assert_eq!(stats.get_grade(), "Intermediate");
         assert_eq!(stats.get_accuracy_grade(), "Good");
@@ -231,20 +345,7 @@ mod tests {
 
     #[test]
     fn test_improvement_calculation() {
-        let stats1 = Stats {
-            timestamp: Utc::now(),
-            test_mode: "Test".to_string(),
-            wpm: 40.0,
-            raw_wpm: 45.0,
-            accuracy: 0.90,
-            error_count: 10,
-            correct_chars: 90,
-            total_chars: 100,
-            test_duration: Duration::from_secs(60),
-            error_frequency: HashMap::new(),
-            speed_over_time: vec![(0.0, 0.0)],
-            consistency_score: 0.7,
-        };
+        let stats1 = Stats::sample();
 
         let stats2 = Stats {
             wpm: 45.0,
@@ -260,4 +361,22 @@ mod tests {
         assert_eq!(improvement.error_count_change, -5);
         assert_eq!(improvement.consistency_change, 0.1);
     }
+
+    #[test]
+    fn test_compare_key_error_rates() {
+        let stats = Stats {
+            error_count: 1,
+            correct_chars: 9,
+            total_chars: 10,
+            error_frequency: HashMap::from([('e', 1)]),
+            char_attempts: HashMap::from([('e', 10), ('t', 4)]),
+            ..Stats::sample()
+        };
+
+        let report = stats.compare_key_error_rates(&[('e', 0.5), ('t', 0.2), ('q', 0.3)]);
+        assert_eq!(report[0].after, Some(0.1));
+        assert_eq!(report[1].after, Some(0.0));
+        assert_eq!(report[2].after, None);
+        assert!(report[0].get_summary().contains("improved"));
+    }
 }
\ No newline at end of file
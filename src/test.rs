@@ -1,9 +1,150 @@
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::{app::TestMode, config::Config, utils};
+use crate::{
+    app::TestMode,
+    code::{self, CodeLanguage, CodeSource},
+    config::{Config, TestSettings},
+    daily,
+    ghost,
+    highlight::{self, TokenKind},
+    input::ErrorPolicy,
+    key_profile::{KeyProfile, UnlockTarget, WordBias},
+    lessons::{Lesson, LESSON_DRILL_WORDS},
+    quotes::QuoteLibrary,
+    stats::LiveStats,
+    transforms::TextTransforms,
+    utils::{self, WordSource},
+    wordlists::{WordList, WordListLibrary},
+};
 
+/// What has to be typed after a word to move on to the next one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WordBreak {
+    Space,
+    /// Enter ends the line; `blank_lines` empty lines follow before the next word.
+    Newline { blank_lines: usize },
+}
+
+/// Whether the text is hidden so it has to be typed from memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MemoryMode {
+    #[default]
+    Off,
+    /// A group of upcoming words is shown for a few seconds, then hidden
+    Preview,
+    /// Words disappear once they've been passed
+    HidePassed,
+}
+
+impl MemoryMode {
+    pub fn all() -> Vec<MemoryMode> {
+        vec![MemoryMode::Off, MemoryMode::Preview, MemoryMode::HidePassed]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            MemoryMode::Off => "Off",
+            MemoryMode::Preview => "Preview",
+            MemoryMode::HidePassed => "Hide passed",
+        }
+    }
+}
+
+/// How a memory test reveals its text.
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryRules {
+    pub mode: MemoryMode,
+    /// Words previewed at a time; 0 previews the whole text at once
+    pub words: usize,
+    pub reveal: Duration,
+}
+
+impl MemoryRules {
+    pub fn from_settings(settings: &TestSettings) -> Self {
+        Self {
+            mode: settings.memory_mode,
+            words: settings.memory_words,
+            reveal: Duration::from_secs_f64(settings.memory_seconds.max(0.0)),
+        }
+    }
+}
+
+/// Conditions that end a run early as failed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FailureRules {
+    pub sudden_death: bool,
+    /// Percentages, checked once the grace period is over
+    pub min_accuracy: Option<f64>,
+    pub min_wpm: Option<f64>,
+    pub grace_period: Duration,
+}
+
+impl FailureRules {
+    pub fn from_settings(settings: &TestSettings) -> Self {
+        Self {
+            sudden_death: settings.sudden_death,
+            min_accuracy: settings.min_accuracy,
+            min_wpm: settings.min_wpm,
+            grace_period: Duration::from_secs(settings.failure_grace_seconds as u64),
+        }
+    }
+
+    /// Why a run with these live stats has failed, if it has.
+    pub fn check(&self, stats: &LiveStats, elapsed: Duration) -> Option<String> {
+        if self.sudden_death && stats.error_count > 0 {
+            return Some("Sudden death: first error".to_string());
+        }
+        if elapsed < self.grace_period {
+            return None;
+        }
+        if let Some(min_accuracy) = self.min_accuracy
+            && stats.accuracy * 100.0 < min_accuracy
+        {
+            return Some(format!("Accuracy fell below {:.0}%", min_accuracy));
+        }
+        if let Some(min_wpm) = self.min_wpm
+            && stats.wpm < min_wpm
+        {
+            return Some(format!("Speed fell below {:.0} WPM", min_wpm));
+        }
+        None
+    }
+}
+
+#[derive(Clone)]
 pub struct Test {
     text: String,
+    // Identifies the text for ghost replays; fixed at creation even if the text streams
+    text_key: String,
+    words: Vec<String>,
+    breaks: Vec<WordBreak>,
+    // Indentation shown before a word but skipped rather than typed
+    indents: Vec<String>,
+    tab_width: usize,
+    error_policy: ErrorPolicy,
+    ascii_folding: bool,
+    failure_rules: FailureRules,
+    memory: MemoryRules,
+    blind: bool,
+    // First word of the group being previewed and when it was shown
+    memory_window: (usize, Instant),
+    source: Option<String>,
+    language: Option<CodeLanguage>,
+    // Token kind of each char of each word, when the language is known
+    syntax: Vec<Vec<TokenKind>>,
+    word_source: Option<WordSource>,
+    word_list: Option<WordList>,
+    transforms: TextTransforms,
+    word_bias: Option<WordBias>,
+    seed: u64,
+    // The same text is likely to come up again, so its ghost is worth keeping
+    repeatable: bool,
+    // Continues from the seed so streamed batches are reproducible too
+    rng: StdRng,
     mode: TestMode,
     start_time: Option<Instant>,
     duration_limit: Option<Duration>,
@@ -12,22 +153,160 @@ pub struct Test {
 
 impl Test {
     pub fn new(mode: &TestMode, config: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::build(mode, config, thread_rng().r#gen(), true)
+    }
+
+    /// Generates the text from `seed`, the mode and the settings alone. Adaptive
+    /// weighting follows the local key profile, so it's left out.
+    pub fn with_seed(mode: &TestMode, config: &Config, seed: u64) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::build(mode, config, seed, false)
+    }
+
+    fn build(mode: &TestMode, config: &Config, seed: u64, adaptive: bool) -> Result<Self, Box<dyn std::error::Error>> {
+        // The daily challenge's text comes from its date alone
+        let seed = match mode {
+            TestMode::Daily(date) => daily::seed_for(*date),
+            _ => seed,
+        };
+        // Everyone takes the daily challenge under the same rules
+        let daily_settings;
+        let settings = match mode {
+            TestMode::Daily(_) => {
+                daily_settings = daily::test_settings(&config.test_settings);
+                &daily_settings
+            }
+            _ => &config.test_settings,
+        };
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut source = None;
+        let mut language = None;
+        let mut word_source = None;
+        let mut word_list = None;
+        let mut word_bias = None;
+        // Only applied to generated words; restricted drills and real text are left alone
+        let mut transforms = TextTransforms::default();
+        let configured_transforms = TextTransforms::from_settings(&config.test_settings);
         let (text, duration_limit, word_limit) = match mode {
             TestMode::Timed(seconds) => {
-                let text = Self::generate_text_for_duration(*seconds as usize, config)?;
-                (text, Some(Duration::from_secs(*seconds as u64)), None)
+                let generator = config.test_settings.word_source.clone();
+                word_list = Self::load_word_list(&generator)?;
+                if adaptive {
+                    word_bias = Self::load_word_bias(config)?;
+                }
+                let text =
+                    Self::generate_words(&generator, word_list.as_ref(), STREAM_BATCH_SIZE * 2, word_bias.as_ref(), &mut rng);
+                word_source = Some(generator);
+                transforms = configured_transforms;
+                (transforms.apply(&text, &mut rng), Some(Duration::from_secs(*seconds as u64)), None)
             }
             TestMode::WordCount(count) => {
-                let text = utils::generate_random_words(*count as usize);
-                (text, None, Some(*count as usize))
+                let generator = config.test_settings.word_source.clone();
+                word_list = Self::load_word_list(&generator)?;
+                if adaptive {
+                    word_bias = Self::load_word_bias(config)?;
+                }
+                let text = Self::generate_words(&generator, word_list.as_ref(), *count as usize, word_bias.as_ref(), &mut rng);
+                word_source = Some(generator);
+                transforms = configured_transforms;
+                (transforms.apply(&text, &mut rng), None, Some(*count as usize))
+            }
+            TestMode::Daily(_) => {
+                let text = utils::generate_from_source(&daily::DAILY_WORD_SOURCE, daily::DAILY_WORDS, None, &mut rng);
+                word_source = Some(daily::DAILY_WORD_SOURCE);
+                transforms = daily::DAILY_TRANSFORMS;
+                (transforms.apply(&text, &mut rng), None, Some(daily::DAILY_WORDS))
             }
             TestMode::Text(custom_text) => {
                 (custom_text.clone(), None, None)
             }
+            TestMode::WeakKeys(keys) => {
+                let text = utils::generate_focused_practice(keys, WEAK_KEY_DRILL_WORDS, &mut rng);
+                transforms = configured_transforms;
+                (transforms.apply(&text, &mut rng), None, Some(WEAK_KEY_DRILL_WORDS))
+            }
+            TestMode::NgramDrill(ngrams) => {
+                let text = utils::generate_ngram_practice(ngrams, WEAK_KEY_DRILL_WORDS, &mut rng);
+                transforms = configured_transforms;
+                (transforms.apply(&text, &mut rng), None, Some(WEAK_KEY_DRILL_WORDS))
+            }
+            TestMode::LetterUnlock => {
+                let profile = KeyProfile::load()?;
+                let letters = profile.get_unlocked_letters();
+                let focus: Vec<char> = profile
+                    .focus_letter(&UnlockTarget::from_settings(&config.test_settings))
+                    .into_iter()
+                    .collect();
+                let text = utils::generate_restricted_practice(&letters, &focus, LETTER_UNLOCK_DRILL_WORDS, &mut rng);
+                (text, None, Some(LETTER_UNLOCK_DRILL_WORDS))
+            }
+            TestMode::Lesson(index) => {
+                (Lesson::generate_drill(*index, &mut rng), None, Some(LESSON_DRILL_WORDS))
+            }
+            TestMode::Code(code_source) => {
+                let snippet = match code_source {
+                    CodeSource::Bundled => code::random_bundled_snippet(&mut rng),
+                    CodeSource::File { path, language } => code::load_snippet(path, *language, &mut rng)?,
+                };
+                source = Some(snippet.name);
+                language = snippet.language;
+                (snippet.code, None, None)
+            }
+            TestMode::Quote(length) => {
+                let library = QuoteLibrary::load()?;
+                let quote = library
+                    .random(*length, &mut rng)
+                    .ok_or_else(|| format!("No {} quotes available", length.display_name().to_lowercase()))?;
+                source = Some(quote.source.clone());
+                (quote.text.clone(), None, None)
+            }
+        };
+        // Typed input is kept composed too, see `InputHandler::handle_character`
+        let text: String = text.nfc().collect();
+
+        let (words, breaks, indents, syntax) = if matches!(mode, TestMode::Code(_)) {
+            let (words, breaks, indents, offsets) = split_lines(&text, !config.test_settings.auto_indent);
+            let syntax = match language {
+                Some(language) => {
+                    let kinds = highlight::highlight(&text, language);
+                    words
+                        .iter()
+                        .zip(offsets)
+                        .map(|(word, offset)| word.grapheme_indices(true).map(|(i, _)| kinds[offset + i]).collect())
+                        .collect()
+                }
+                None => Vec::new(),
+            };
+            (words, breaks, indents, syntax)
+        } else {
+            let words: Vec<String> = text.split_whitespace().map(str::to_string).collect();
+            let count = words.len();
+            (words, vec![WordBreak::Space; count], vec![String::new(); count], Vec::new())
         };
 
         Ok(Self {
+            text_key: ghost::text_key(&text),
             text,
+            words,
+            breaks,
+            indents,
+            tab_width: config.test_settings.tab_width.max(1),
+            error_policy: settings.error_policy,
+            ascii_folding: config.test_settings.ascii_folding,
+            failure_rules: FailureRules::from_settings(settings),
+            memory: MemoryRules::from_settings(settings),
+            blind: settings.blind_mode,
+            memory_window: (0, Instant::now()),
+            source,
+            language,
+            syntax,
+            word_source,
+            word_list,
+            transforms,
+            word_bias,
+            seed,
+            repeatable: !adaptive
+                || matches!(mode, TestMode::Quote(_) | TestMode::Text(_) | TestMode::Code(_) | TestMode::Daily(_)),
+            rng,
             mode: mode.clone(),
             start_time: None,
             duration_limit,
@@ -35,16 +314,187 @@ impl Test {
         })
     }
 
+    fn load_word_list(source: &WordSource) -> Result<Option<WordList>, Box<dyn std::error::Error>> {
+        let WordSource::List(name) = source else {
+            return Ok(None);
+        };
+        let library = WordListLibrary::load()?;
+        let list = library.get(name).ok_or_else(|| format!("Word list '{}' not found", name))?;
+        Ok(Some(list.clone()))
+    }
+
+    fn generate_words(
+        source: &WordSource,
+        list: Option<&WordList>,
+        count: usize,
+        bias: Option<&WordBias>,
+        rng: &mut StdRng,
+    ) -> String {
+        match list {
+            Some(list) => list.generate(count, bias, rng),
+            None => utils::generate_from_source(source, count, bias, rng),
+        }
+    }
+
+    fn load_word_bias(config: &Config) -> Result<Option<WordBias>, Box<dyn std::error::Error>> {
+        let strength = config.test_settings.adaptive_strength;
+        if strength <= 0.0 {
+            return Ok(None);
+        }
+        Ok(KeyProfile::load()?.word_bias(strength))
+    }
+
     pub fn start(&mut self) {
         if self.start_time.is_none() {
             self.start_time = Some(Instant::now());
         }
     }
 
+    /// A fresh, unstarted copy of this test on exactly the same text.
+    pub fn restart(&self) -> Self {
+        Self {
+            start_time: None,
+            memory_window: (0, Instant::now()),
+            repeatable: true,
+            ..self.clone()
+        }
+    }
+
+    /// Quotes, loaded texts, code, the daily challenge, explicitly seeded
+    /// texts and retries; one-off generated texts aren't.
+    pub fn is_repeatable(&self) -> bool {
+        self.repeatable
+    }
+
+    pub fn get_text_key(&self) -> &str {
+        &self.text_key
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.start_time.is_some()
+    }
+
+    #[cfg(test)]
     pub fn get_text(&self) -> &str {
         &self.text
     }
 
+    pub fn get_words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn get_error_policy(&self) -> ErrorPolicy {
+        self.error_policy
+    }
+
+    pub fn get_ascii_folding(&self) -> bool {
+        self.ascii_folding
+    }
+
+    pub fn get_failure_rules(&self) -> &FailureRules {
+        &self.failure_rules
+    }
+
+    pub fn get_language(&self) -> Option<CodeLanguage> {
+        self.language
+    }
+
+    pub fn get_word_break(&self, index: usize) -> WordBreak {
+        self.breaks.get(index).copied().unwrap_or(WordBreak::Space)
+    }
+
+    pub fn get_word_syntax(&self, index: usize) -> &[TokenKind] {
+        self.syntax.get(index).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn get_word_indent(&self, index: usize) -> &str {
+        self.indents.get(index).map_or("", String::as_str)
+    }
+
+    pub fn get_tab_width(&self) -> usize {
+        self.tab_width
+    }
+
+    /// Words that have to be submitted for the test to finish.
+    pub fn get_word_count(&self) -> usize {
+        self.word_limit.unwrap_or(self.words.len()).min(self.words.len())
+    }
+
+    /// Attribution for quote tests.
+    pub fn get_source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Mode name as recorded in history, so memory and blind runs are compared separately.
+    pub fn get_mode_name(&self) -> String {
+        let mut name = self.mode.display_name();
+        if self.memory.mode != MemoryMode::Off {
+            name.push_str(&format!(" [Memory: {}]", self.memory.mode.display_name()));
+        }
+        if self.blind {
+            name.push_str(" [Blind]");
+        }
+        name
+    }
+
+    /// Whether mistakes are kept out of sight until the results.
+    pub fn is_blind(&self) -> bool {
+        self.blind
+    }
+
+    /// Starts previewing the next group of words once the cursor reaches it.
+    pub fn track_memory_window(&mut self, current_word: usize) {
+        if self.memory.mode != MemoryMode::Preview || self.memory.words == 0 {
+            return;
+        }
+        let window_start = current_word - current_word % self.memory.words;
+        if window_start != self.memory_window.0 {
+            self.memory_window = (window_start, Instant::now());
+        }
+    }
+
+    /// How much longer the current preview stays up, while it's showing.
+    pub fn memory_time_left(&self) -> Option<Duration> {
+        if self.memory.mode != MemoryMode::Preview {
+            return None;
+        }
+        self.memory.reveal.checked_sub(self.memory_window.1.elapsed()).filter(|left| !left.is_zero())
+    }
+
+    /// Whether word `index` is hidden and has to be typed from memory.
+    pub fn is_word_hidden(&self, index: usize, current_word: usize) -> bool {
+        match self.memory.mode {
+            MemoryMode::Off => false,
+            MemoryMode::HidePassed => index < current_word,
+            MemoryMode::Preview => {
+                let start = self.memory_window.0;
+                let in_window = self.memory.words == 0 || (start..start + self.memory.words).contains(&index);
+                !in_window || self.memory_time_left().is_none()
+            }
+        }
+    }
+
+    /// The seed that regenerates this text with `--seed`, for texts that come
+    /// from the seed alone. Drills built from history or the key profile, quotes,
+    /// which can come from local files, and loaded text have none.
+    pub fn get_seed(&self) -> Option<u64> {
+        let from_seed = match self.mode {
+            TestMode::Timed(_) | TestMode::WordCount(_) => self.word_bias.is_none(),
+            TestMode::Lesson(_) | TestMode::Daily(_) | TestMode::Code(CodeSource::Bundled) => true,
+            _ => false,
+        };
+        from_seed.then_some(self.seed)
+    }
+
+    pub fn get_transforms(&self) -> TextTransforms {
+        self.transforms
+    }
+
+    /// The generator behind the text, for modes that generate it.
+    pub fn get_word_source(&self) -> Option<&WordSource> {
+        self.word_source.as_ref()
+    }
+
     pub fn get_mode(&self) -> &TestMode {
         &self.mode
     }
@@ -58,11 +508,13 @@ impl Test {
         false
     }
 
+    /// Time since the first keystroke. Timed tests only end on the next tick,
+    /// so it stops at their limit rather than counting the overrun.
     pub fn elapsed_time(&self) -> Duration {
-        if let Some(start) = self.start_time {
-            start.elapsed()
-        } else {
-            Duration::from_secs(0)
+        match (self.start_time, self.duration_limit) {
+            (Some(start), Some(limit)) => start.elapsed().min(limit),
+            (Some(start), None) => start.elapsed(),
+            (None, _) => Duration::from_secs(0),
         }
     }
 
@@ -79,33 +531,100 @@ impl Test {
         }
     }
 
-    fn generate_text_for_duration(seconds: usize, _config: &Config) -> Result<String, Box<dyn std::error::Error>> {
-        let estimated_wpm = 40.0;
-        let minutes = seconds as f64 / 60.0;
-        let estimated_words = (estimated_wpm * minutes * 1.2) as usize;
-        Ok(utils::generate_random_words(estimated_words.max(50))) 
+    /// Timed tests have no fixed end, so their text keeps growing as it's typed.
+    pub fn is_endless(&self) -> bool {
+        self.duration_limit.is_some() && self.word_limit.is_none()
+    }
+
+    /// Appends freshly generated words once the cursor gets within
+    /// `STREAM_LOOKAHEAD` words of the end of an endless test's text.
+    pub fn ensure_words_ahead(&mut self, current_word: usize) {
+        if !self.is_endless() || self.words.len() > current_word + STREAM_LOOKAHEAD {
+            return;
+        }
+        let batch = Self::generate_words(
+            &self.word_source.clone().unwrap_or_default(),
+            self.word_list.as_ref(),
+            STREAM_BATCH_SIZE,
+            self.word_bias.as_ref(),
+            &mut self.rng,
+        );
+        let batch = self.transforms.apply(&batch, &mut self.rng);
+        for word in batch.split_whitespace() {
+            if !self.text.is_empty() {
+                self.text.push(' ');
+            }
+            self.text.push_str(word);
+            self.words.push(word.to_string());
+            self.breaks.push(WordBreak::Space);
+            self.indents.push(String::new());
+        }
     }
 }
 
-// Sample texts
-pub const SAMPLE_TEXTS: &[&str] = &[
-    "The quick brown fox jumps over the lazy dog. This pangram contains every letter of the alphabet at least once, making it perfect for typing practice.",
-    "In a hole in the ground there lived a hobbit. Not a nasty, dirty, wet hole, filled with the ends of worms and an oozy smell, nor yet a dry, bare, sandy hole with nothing in it to sit down on or to eat.",
-    "It was the best of times, it was the worst of times, it was the age of wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity.",
-    "Call me Ishmael. Some years ago never mind how long precisely having little or no money in my purse, and nothing particular to interest me on shore, I thought I would sail about a little.",
-    "To be or not to be, that is the question: Whether 'tis nobler in the mind to suffer the slings and arrows of outrageous fortune, or to take arms against a sea of troubles.",
-];
+/// Splits code into words along with the break after each word and the
+/// indentation before it. With `type_indentation`, a line's indentation is
+/// part of its first word instead, so it has to be typed.
+/// Also returns the byte offset in `text` where each word starts.
+fn split_lines(text: &str, type_indentation: bool) -> (Vec<String>, Vec<WordBreak>, Vec<String>, Vec<usize>) {
+    let mut words = Vec::new();
+    let mut breaks = Vec::new();
+    let mut indents = Vec::new();
+    let mut offsets = Vec::new();
+    let mut blank_lines = 0;
+    let offset_of = |part: &str| part.as_ptr() as usize - text.as_ptr() as usize;
 
-pub fn load_sample_text(index: usize) -> String {
-    SAMPLE_TEXTS.get(index).unwrap_or(&SAMPLE_TEXTS[0]).to_string()
-}
+    for line in text.lines() {
+        let content = line.trim();
+        if content.is_empty() {
+            if !words.is_empty() {
+                blank_lines += 1;
+            }
+            continue;
+        }
+        if let Some(last) = breaks.last_mut() {
+            *last = WordBreak::Newline { blank_lines };
+        }
+        blank_lines = 0;
 
-pub fn get_random_sample_text() -> String {
-    use rand::seq::SliceRandom;
-    let mut rng = rand::thread_rng();
-    SAMPLE_TEXTS.choose(&mut rng).unwrap_or(&SAMPLE_TEXTS[0]).to_string()
+        let indent = &line[..line.len() - line.trim_start().len()];
+        for (i, token) in content.split_whitespace().enumerate() {
+            if i == 0 && type_indentation {
+                words.push(format!("{}{}", indent, token));
+                indents.push(String::new());
+                offsets.push(offset_of(line));
+            } else {
+                words.push(token.to_string());
+                indents.push(if i == 0 { indent.to_string() } else { String::new() });
+                offsets.push(offset_of(token));
+            }
+            breaks.push(WordBreak::Space);
+        }
+    }
+    (words, breaks, indents, offsets)
 }
 
+const STREAM_BATCH_SIZE: usize = 50;
+const WEAK_KEY_DRILL_WORDS: usize = 30;
+const LETTER_UNLOCK_DRILL_WORDS: usize = 30;
+const STREAM_LOOKAHEAD: usize = 30;
+
+// Sample texts as (text, source) pairs; these seed the bundled quote collection
+pub const SAMPLE_TEXTS: &[(&str, &str)] = &[
+    ("The quick brown fox jumps over the lazy dog. This pangram contains every letter of the alphabet at least once, making it perfect for typing practice.", "Traditional pangram"),
+    ("In a hole in the ground there lived a hobbit. Not a nasty, dirty, wet hole, filled with the ends of worms and an oozy smell, nor yet a dry, bare, sandy hole with nothing in it to sit down on or to eat.", "J.R.R. Tolkien, The Hobbit"),
+    ("It was the best of times, it was the worst of times, it was the age of wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity.", "Charles Dickens, A Tale of Two Cities"),
+    ("Call me Ishmael. Some years ago never mind how long precisely having little or no money in my purse, and nothing particular to interest me on shore, I thought I would sail about a little.", "Herman Melville, Moby-Dick"),
+    ("To be or not to be, that is the question: Whether 'tis nobler in the mind to suffer the slings and arrows of outrageous fortune, or to take arms against a sea of troubles.", "William Shakespeare, Hamlet"),
+    ("Not all those who wander are lost.", "J.R.R. Tolkien, The Fellowship of the Ring"),
+    ("The only thing we have to fear is fear itself.", "Franklin D. Roosevelt, First Inaugural Address"),
+    ("Simplicity is prerequisite for reliability.", "Edsger W. Dijkstra"),
+    ("Four score and seven years ago our fathers brought forth on this continent, a new nation, conceived in Liberty, and dedicated to the proposition that all men are created equal. Now we are engaged in a great civil war, testing whether that nation, or any nation so conceived and so dedicated, can long endure. We are met on a great battle-field of that war.", "Abraham Lincoln, Gettysburg Address"),
+    ("It is a truth universally acknowledged, that a single man in possession of a good fortune, must be in want of a wife. However little known the feelings or views of such a man may be on his first entering a neighbourhood, this truth is so well fixed in the minds of the surrounding families, that he is considered the rightful property of some one or other of their daughters.", "Jane Austen, Pride and Prejudice"),
+    ("We hold these truths to be self-evident, that all men are created equal, that they are endowed by their Creator with certain unalienable Rights, that among these are Life, Liberty and the pursuit of Happiness. That to secure these rights, Governments are instituted among Men, deriving their just powers from the consent of the governed. That whenever any Form of Government becomes destructive of these ends, it is the Right of the People to alter or to abolish it, and to institute new Government, laying its foundation on such principles and organizing its powers in such form, as to them shall seem most likely to effect their Safety and Happiness.", "United States Declaration of Independence"),
+    ("Happy families are all alike; every unhappy family is unhappy in its own way. Everything was in confusion in the Oblonskys' house. The wife had discovered that the husband was carrying on an intrigue with a French girl, who had been a governess in their family, and she had announced to her husband that she could not go on living in the same house with him. This position of affairs had now lasted three days, and not only the husband and wife themselves, but all the members of their family and household, were painfully conscious of it. Every person in the house felt that there was no sense in their living together, and that the stray people brought together by chance in any inn had more in common with one another than they, the members of the family and household of the Oblonskys.", "Leo Tolstoy, Anna Karenina"),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,9 +642,150 @@ mod tests {
     fn test_timed_mode() {
         let config = Config::default();
         let mode = TestMode::Timed(60);
-        let test = Test::new(&mode, &config).unwrap();
+        let mut test = Test::new(&mode, &config).unwrap();
         assert!(test.duration_limit.is_some());
         assert_eq!(test.duration_limit.unwrap(), Duration::from_secs(60));
+
+        test.start_time = Instant::now().checked_sub(Duration::from_millis(60_090));
+        assert_eq!(test.elapsed_time(), Duration::from_secs(60));
+        assert!(test.is_complete());
+        assert!(!test.is_repeatable());
+        assert!(test.restart().is_repeatable());
+    }
+
+    #[test]
+    fn test_timed_mode_streams_words() {
+        let config = Config::default();
+        let mut test = Test::new(&TestMode::Timed(30), &config).unwrap();
+        let initial = test.get_words().len();
+
+        test.ensure_words_ahead(0);
+        assert_eq!(test.get_words().len(), initial);
+
+        test.ensure_words_ahead(initial - 1);
+        assert_eq!(test.get_words().len(), initial + STREAM_BATCH_SIZE);
+        assert_eq!(test.get_text().split_whitespace().count(), test.get_words().len());
+    }
+
+    #[test]
+    fn test_same_seed_same_text() {
+        let mut config = Config::default();
+        config.test_settings.punctuation = true;
+        config.test_settings.numbers = true;
+        let mut first = Test::with_seed(&TestMode::Timed(30), &config, 7).unwrap();
+        let mut second = Test::with_seed(&TestMode::Timed(30), &config, 7).unwrap();
+        let initial = first.get_words().len();
+        first.ensure_words_ahead(initial - 1);
+        second.ensure_words_ahead(initial - 1);
+        assert_eq!(first.get_text(), second.get_text());
+        assert!(first.word_bias.is_none());
+        assert_eq!(first.get_seed(), Some(7));
+    }
+
+    #[test]
+    fn test_daily_text_ignores_settings() {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let mut config = Config::default();
+        let first = Test::with_seed(&TestMode::Daily(date), &config, 1).unwrap();
+        config.test_settings.word_source = WordSource::Symbols;
+        config.test_settings.numbers = true;
+        let second = Test::new(&TestMode::Daily(date), &config).unwrap();
+        assert_eq!(first.get_text(), second.get_text());
+        assert_eq!(second.get_seed(), Some(20261018));
+        assert_eq!(first.get_words().len(), daily::DAILY_WORDS);
+    }
+
+    #[test]
+    fn test_seed_only_for_seeded_text() {
+        let config = Config::default();
+        let words = Test::with_seed(&TestMode::WordCount(10), &config, 7).unwrap();
+        assert_eq!(words.get_seed(), Some(7));
+        let weak_keys = Test::with_seed(&TestMode::WeakKeys(vec!['q']), &config, 7).unwrap();
+        assert_eq!(weak_keys.get_seed(), None);
+        let text = Test::with_seed(&TestMode::Text("some text".to_string()), &config, 7).unwrap();
+        assert_eq!(text.get_seed(), None);
+    }
+
+    #[test]
+    fn test_daily_rules_ignore_settings() {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let mut config = Config::default();
+        config.test_settings.error_policy = ErrorPolicy::StopOnWord;
+        config.test_settings.sudden_death = true;
+        config.test_settings.memory_mode = MemoryMode::HidePassed;
+        config.test_settings.blind_mode = true;
+        let test = Test::new(&TestMode::Daily(date), &config).unwrap();
+        assert_eq!(test.get_error_policy(), ErrorPolicy::Off);
+        assert!(!test.get_failure_rules().sudden_death);
+        assert!(!test.is_word_hidden(0, 1));
+        assert!(!test.is_blind());
+    }
+
+    #[test]
+    fn test_memory_preview_window() {
+        let mut config = Config::default();
+        config.test_settings.memory_mode = MemoryMode::Preview;
+        config.test_settings.memory_words = 5;
+        config.test_settings.memory_seconds = 60.0;
+        let mut test = Test::new(&TestMode::WordCount(20), &config).unwrap();
+        assert!(!test.is_word_hidden(4, 0));
+        assert!(test.is_word_hidden(5, 0));
+
+        test.track_memory_window(7);
+        assert!(test.is_word_hidden(4, 7));
+        assert!(!test.is_word_hidden(9, 7));
+        assert_eq!(test.get_mode_name(), "Word Count (20) [Memory: Preview]");
+
+        config.test_settings.memory_seconds = 0.0;
+        let test = Test::new(&TestMode::WordCount(20), &config).unwrap();
+        assert!(test.is_word_hidden(0, 0));
+
+        config.test_settings.memory_mode = MemoryMode::HidePassed;
+        let test = Test::new(&TestMode::WordCount(20), &config).unwrap();
+        assert!(test.is_word_hidden(2, 3));
+        assert!(!test.is_word_hidden(3, 3));
+        assert_eq!(test.get_mode_name(), "Word Count (20) [Memory: Hide passed]");
+
+        config.test_settings.blind_mode = true;
+        let test = Test::new(&TestMode::WordCount(20), &config).unwrap();
+        assert_eq!(test.get_mode_name(), "Word Count (20) [Memory: Hide passed] [Blind]");
+    }
+
+    #[test]
+    fn test_split_lines_keeps_line_structure() {
+        let text = "fn main() {\n    run();\n\n}";
+        let (words, breaks, indents, offsets) = split_lines(text, false);
+        assert_eq!(words, vec!["fn", "main()", "{", "run();", "}"]);
+        assert_eq!(breaks[2], WordBreak::Newline { blank_lines: 0 });
+        assert_eq!(breaks[3], WordBreak::Newline { blank_lines: 1 });
+        assert_eq!(indents[3], "    ");
+
+        assert_eq!(offsets[3], text.find("run").unwrap());
+
+        let (words, _, indents, offsets) = split_lines(text, true);
+        assert_eq!(offsets[3], text.find("    run").unwrap());
+        assert_eq!(words[3], "    run();");
+        assert_eq!(indents[3], "");
+    }
+
+    #[test]
+    fn test_failure_rules() {
+        let rules = FailureRules {
+            sudden_death: false,
+            min_accuracy: Some(90.0),
+            min_wpm: Some(30.0),
+            grace_period: Duration::from_secs(5),
+        };
+        let shaky = LiveStats { wpm: 50.0, accuracy: 0.8, error_count: 4 };
+        assert_eq!(rules.check(&shaky, Duration::from_secs(2)), None);
+        assert!(rules.check(&shaky, Duration::from_secs(6)).unwrap().contains("Accuracy"));
+
+        let slow = LiveStats { wpm: 20.0, accuracy: 1.0, error_count: 0 };
+        assert!(rules.check(&slow, Duration::from_secs(6)).unwrap().contains("WPM"));
+
+        let sudden_death = FailureRules { sudden_death: true, ..FailureRules::default() };
+        let one_slip = LiveStats { wpm: 80.0, accuracy: 0.99, error_count: 1 };
+        assert!(sudden_death.check(&one_slip, Duration::ZERO).is_some());
     }
 
     #[test]
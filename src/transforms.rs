@@ -0,0 +1,169 @@
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::config::TestSettings;
+
+// Roughly one word in this many gets a comma, a number, quotes or a random capital.
+const COMMA_EVERY: u32 = 8;
+const NUMBER_EVERY: u32 = 10;
+const WRAP_EVERY: u32 = 20;
+const CAPITAL_EVERY: u32 = 15;
+// Sentence length range, in words
+const MIN_SENTENCE: usize = 5;
+const MAX_SENTENCE: usize = 12;
+
+/// Optional changes layered on generated words so they read more like real
+/// writing. Each one is a step in `apply`'s pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextTransforms {
+    pub punctuation: bool,
+    pub capitalization: bool,
+    pub numbers: bool,
+}
+
+type Step = fn(Vec<String>, &mut dyn RngCore) -> Vec<String>;
+
+impl TextTransforms {
+    pub fn from_settings(settings: &TestSettings) -> Self {
+        Self {
+            punctuation: settings.punctuation,
+            capitalization: settings.capitalization,
+            numbers: settings.numbers,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.punctuation && !self.capitalization && !self.numbers
+    }
+
+    pub fn get_summary(&self) -> String {
+        let names: Vec<&str> = [
+            (self.punctuation, "Punctuation"),
+            (self.capitalization, "Capitals"),
+            (self.numbers, "Numbers"),
+        ]
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|&(_, name)| name)
+        .collect();
+        if names.is_empty() { "None".to_string() } else { names.join(", ") }
+    }
+
+    /// Runs the enabled steps over `text`, keeping the word count the same.
+    /// Capitalization goes last so it can see where sentences start.
+    pub fn apply(&self, text: &str, rng: &mut dyn RngCore) -> String {
+        let steps: [(bool, Step); 3] = [
+            (self.numbers, add_numbers),
+            (self.punctuation, add_punctuation),
+            (self.capitalization, add_capitals),
+        ];
+        let mut words: Vec<String> = text.split_whitespace().map(str::to_string).collect();
+        for (_, step) in steps.iter().filter(|(enabled, _)| *enabled) {
+            words = step(words, rng);
+        }
+        words.join(" ")
+    }
+}
+
+fn add_numbers(words: Vec<String>, rng: &mut dyn RngCore) -> Vec<String> {
+    words
+        .into_iter()
+        .map(|word| {
+            if rng.gen_ratio(1, NUMBER_EVERY) {
+                let digits = rng.gen_range(1..=4);
+                rng.gen_range(10u32.pow(digits - 1)..10u32.pow(digits)).to_string()
+            } else {
+                word
+            }
+        })
+        .collect()
+}
+
+fn add_punctuation(words: Vec<String>, rng: &mut dyn RngCore) -> Vec<String> {
+    let count = words.len();
+    let mut sentence_end = rng.gen_range(MIN_SENTENCE..=MAX_SENTENCE);
+    let mut sentence_len = 0;
+    let mut result = Vec::with_capacity(count);
+
+    for (i, mut word) in words.into_iter().enumerate() {
+        sentence_len += 1;
+        if rng.gen_ratio(1, WRAP_EVERY) {
+            word = if rng.gen_bool(0.5) { format!("\"{}\"", word) } else { format!("({})", word) };
+        }
+        if sentence_len >= sentence_end || i + 1 == count {
+            word.push(match rng.gen_range(0..10) {
+                0 => '?',
+                1 => '!',
+                _ => '.',
+            });
+            sentence_len = 0;
+            sentence_end = rng.gen_range(MIN_SENTENCE..=MAX_SENTENCE);
+        } else if rng.gen_ratio(1, COMMA_EVERY) {
+            word.push(',');
+        }
+        result.push(word);
+    }
+    result
+}
+
+fn add_capitals(words: Vec<String>, rng: &mut dyn RngCore) -> Vec<String> {
+    let mut sentence_start = true;
+    words
+        .into_iter()
+        .map(|word| {
+            let capitalize = sentence_start || rng.gen_ratio(1, CAPITAL_EVERY);
+            sentence_start = word.ends_with(['.', '?', '!']);
+            if capitalize { capitalize_word(&word) } else { word }
+        })
+        .collect()
+}
+
+/// Capitalizes the first letter, skipping any opening quote or bracket.
+fn capitalize_word(word: &str) -> String {
+    match word.char_indices().find(|(_, c)| c.is_alphabetic()) {
+        Some((i, first)) => {
+            let rest = &word[i + first.len_utf8()..];
+            format!("{}{}{}", &word[..i], first.to_uppercase(), rest)
+        }
+        None => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    const WORDS: &str = "the quick brown fox jumps over the lazy dog and runs far away from here today";
+
+    #[test]
+    fn test_transforms_keep_word_count() {
+        let all = TextTransforms { punctuation: true, capitalization: true, numbers: true };
+        for _ in 0..20 {
+            let text = all.apply(WORDS, &mut thread_rng());
+            assert_eq!(text.split_whitespace().count(), WORDS.split_whitespace().count());
+            assert!(text.ends_with(['.', '?', '!']));
+            assert!(text.starts_with(|c: char| c.is_uppercase() || c.is_ascii_digit() || c == '"' || c == '('));
+        }
+        assert_eq!(TextTransforms::default().apply(WORDS, &mut thread_rng()), WORDS);
+    }
+
+    #[test]
+    fn test_capitals_follow_sentence_ends() {
+        let mut rng = thread_rng();
+        let words: Vec<String> = ["one.", "two", "(three", "four?", "\"five\""].iter().map(|w| w.to_string()).collect();
+        let capitalized = add_capitals(words, &mut rng);
+        assert_eq!(capitalized[0], "One.");
+        assert_eq!(capitalized[1], "Two");
+        assert_eq!(capitalized[4], "\"Five\"");
+        assert_eq!(capitalize_word("(three"), "(Three");
+    }
+
+    #[test]
+    fn test_summary() {
+        let transforms = TextTransforms { punctuation: true, capitalization: false, numbers: true };
+        assert_eq!(transforms.get_summary(), "Punctuation, Numbers");
+        assert_eq!(TextTransforms::default().get_summary(), "None");
+    }
+}
@@ -8,7 +8,16 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::{App, Screen};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::app::{App, ModeSelectionColumn, Screen, TestMode};
+use crate::daily;
+use crate::input::{compare_graphemes, ErrorPolicy, GraphemeMatch};
+use crate::lessons::{Lesson, LESSONS};
+use crate::pace::{self, PaceTarget};
+use crate::test::WordBreak;
+use crate::utils::display_width;
 
 pub fn draw(f: &mut Frame, app: &mut App) {
     match app.current_screen {
@@ -17,6 +26,9 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         Screen::Results => draw_results(f, app),
         Screen::History => draw_history(f, app),
         Screen::ModeSelection => draw_mode_selection(f, app),
+        Screen::FilePicker => draw_file_picker(f, app),
+        Screen::Lessons => draw_lessons(f, app),
+        Screen::Daily => draw_daily(f, app),
     }
 }
 
@@ -77,11 +89,25 @@ fn draw_menu(f: &mut Frame, app: &App) {
                 Style::default().fg(app.config.theme.accent()),
             ),
         ]),
+        Line::from(vec![
+            Span::styled("Word Source: ", Style::default().fg(app.config.theme.text())),
+            Span::styled(
+                app.config.test_settings.word_source.display_name(),
+                Style::default().fg(app.config.theme.accent()),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Stop on Error: ", Style::default().fg(app.config.theme.text())),
+            Span::styled(
+                app.config.test_settings.error_policy.display_name(),
+                Style::default().fg(app.config.theme.accent()),
+            ),
+        ]),
         Line::from(""),
         Line::from("Keybindings:"),
         Line::from("  ↑/↓ or j/k - Navigate"),
         Line::from("  Enter - Select"),
-        Line::from("  1-4 - Quick select"),
+        Line::from("  1-7 - Quick select"),
         Line::from("  q - Quit"),
     ];
 
@@ -98,8 +124,12 @@ fn draw_menu(f: &mut Frame, app: &App) {
     f.render_widget(info_panel, main_chunks[1]);
 
     // Footer
-    let footer = Paragraph::new("Press Enter to start, or use number keys for quick selection")
-        .style(Style::default().fg(app.config.theme.muted()))
+    let (footer_text, footer_color) = match &app.status_message {
+        Some(message) => (message.as_str(), app.config.theme.error()),
+        None => ("Press Enter to start, or use number keys for quick selection", app.config.theme.muted()),
+    };
+    let footer = Paragraph::new(footer_text)
+        .style(Style::default().fg(footer_color))
         .alignment(Alignment::Center)
         .block(
             Block::default()
@@ -126,40 +156,438 @@ fn draw_mode_selection(f: &mut Frame, app: &App) {
         );
     f.render_widget(title, chunks[0]);
 
+    let list_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(30), Constraint::Percentage(30)])
+        .split(chunks[1]);
+
     // Mode list
-    let mode_items: Vec<ListItem> = app
-        .available_modes
+    let modes: Vec<String> = app.available_modes.iter().map(|mode| mode.display_name()).collect();
+    let mode_list = selection_list(
+        app,
+        "Available Modes",
+        &modes,
+        app.selected_mode_index,
+        app.mode_selection_column == ModeSelectionColumn::Modes,
+    );
+    f.render_widget(mode_list, list_chunks[0]);
+
+    // Word source list, only used by generated modes
+    let sources: Vec<String> = app
+        .available_word_sources
+        .iter()
+        .map(|source| source.display_name().to_string())
+        .collect();
+    let source_list = selection_list(
+        app,
+        "Word Source",
+        &sources,
+        app.selected_word_source_index,
+        app.mode_selection_column == ModeSelectionColumn::WordSources,
+    );
+    f.render_widget(source_list, list_chunks[1]);
+
+    let option_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(app.available_error_policies.len() as u16 + 2),
+            Constraint::Length(app.available_pace_targets.len() as u16 + 2),
+            Constraint::Length(app.available_memory_modes.len() as u16 + 2),
+            Constraint::Min(0),
+        ])
+        .split(list_chunks[2]);
+
+    let policies: Vec<String> = app
+        .available_error_policies
+        .iter()
+        .map(|policy| policy.display_name().to_string())
+        .collect();
+    let policy_list = selection_list(
+        app,
+        "Stop on Error",
+        &policies,
+        app.selected_error_policy_index,
+        app.mode_selection_column == ModeSelectionColumn::ErrorPolicies,
+    );
+    f.render_widget(policy_list, option_chunks[0]);
+
+    let pace_targets: Vec<String> = app
+        .available_pace_targets
+        .iter()
+        .map(|target| match target {
+            PaceTarget::Fixed => format!("{} ({:.0})", target.display_name(), app.config.test_settings.pace_wpm),
+            _ => target.display_name().to_string(),
+        })
+        .collect();
+    let pace_list = selection_list(
+        app,
+        "Pace Caret",
+        &pace_targets,
+        app.selected_pace_target_index,
+        app.mode_selection_column == ModeSelectionColumn::PaceTargets,
+    );
+    f.render_widget(pace_list, option_chunks[1]);
+
+    let memory_modes: Vec<String> = app
+        .available_memory_modes
+        .iter()
+        .map(|mode| mode.display_name().to_string())
+        .collect();
+    let memory_list = selection_list(
+        app,
+        "Memory",
+        &memory_modes,
+        app.selected_memory_mode_index,
+        app.mode_selection_column == ModeSelectionColumn::MemoryModes,
+    );
+    f.render_widget(memory_list, option_chunks[2]);
+
+    // Text options apply to generated words only; blind mode to every test
+    let transforms = app.pending_transforms;
+    let text_options: Vec<Line> = [
+        (transforms.punctuation, "Punctuation (P)"),
+        (transforms.capitalization, "Capitals (C)"),
+        (transforms.numbers, "Numbers (N)"),
+        (app.pending_blind, "Blind (B)"),
+    ]
+    .iter()
+    .map(|&(enabled, label)| Line::from(format!("[{}] {}", if enabled { "x" } else { " " }, label)))
+    .collect();
+    let text_options = Paragraph::new(text_options)
+        .style(Style::default().fg(app.config.theme.text()))
+        .block(
+            Block::default()
+                .title("Options")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        );
+    f.render_widget(text_options, option_chunks[3]);
+
+    // Instructions
+    let instructions = Paragraph::new("↑/↓ to navigate, ←/→ or Tab to switch list, P/C/N/B to toggle options, Enter to select, Esc/M to return to menu")
+        .style(Style::default().fg(app.config.theme.muted()))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        );
+    f.render_widget(instructions, chunks[2]);
+}
+
+fn selection_list<'a>(app: &App, title: &'a str, items: &[String], selected: usize, focused: bool) -> List<'a> {
+    let list_items: Vec<ListItem> = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let style = if i == selected && focused {
+                Style::default()
+                    .fg(app.config.theme.highlight())
+                    .add_modifier(Modifier::BOLD)
+            } else if i == selected {
+                Style::default().fg(app.config.theme.accent())
+            } else {
+                Style::default().fg(app.config.theme.text())
+            };
+
+            let indicator = if i == selected { "► " } else { "  " };
+            ListItem::new(format!("{}{}", indicator, item)).style(style)
+        })
+        .collect();
+
+    let border_color = if focused { app.config.theme.accent() } else { app.config.theme.border() };
+    List::new(list_items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border_color)),
+        )
+        .style(Style::default().fg(app.config.theme.text()))
+}
+
+fn draw_file_picker(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0), Constraint::Length(3)])
+        .split(f.size());
+
+    let title = Paragraph::new("📂 Load Text File")
+        .style(Style::default().fg(app.config.theme.accent()))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        );
+    f.render_widget(title, chunks[0]);
+
+    let picker = &app.file_picker;
+    let entry_items: Vec<ListItem> = picker
+        .get_entries()
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let style = if i == picker.selected {
+                Style::default()
+                    .fg(app.config.theme.highlight())
+                    .add_modifier(Modifier::BOLD)
+            } else if entry.is_dir {
+                Style::default().fg(app.config.theme.accent())
+            } else {
+                Style::default().fg(app.config.theme.text())
+            };
+
+            let indicator = if i == picker.selected { "► " } else { "  " };
+            let suffix = if entry.is_dir { "/" } else { "" };
+            ListItem::new(format!("{}{}{}", indicator, entry.name, suffix)).style(style)
+        })
+        .collect();
+
+    // Keep the selection in view for long directory listings
+    let visible_rows = chunks[1].height.saturating_sub(2) as usize;
+    let skip = (picker.selected + 1).saturating_sub(visible_rows);
+    let entry_list = List::new(entry_items.into_iter().skip(skip).collect::<Vec<_>>())
+        .block(
+            Block::default()
+                .title(picker.get_current_dir().display().to_string())
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        )
+        .style(Style::default().fg(app.config.theme.text()));
+
+    f.render_widget(entry_list, chunks[1]);
+
+    let (footer_text, footer_color) = match &picker.message {
+        Some(message) => (message.clone(), app.config.theme.error()),
+        None => (
+            "↑/↓ to navigate, Enter to open, Backspace for parent, Esc/M to return to menu".to_string(),
+            app.config.theme.muted(),
+        ),
+    };
+    let instructions = Paragraph::new(footer_text)
+        .style(Style::default().fg(footer_color))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        );
+    f.render_widget(instructions, chunks[2]);
+}
+
+fn draw_lessons(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0), Constraint::Length(3)])
+        .split(f.size());
+
+    let passed = (0..LESSONS.len()).filter(|&i| app.lesson_progress.is_passed(i)).count();
+    let title = Paragraph::new(format!("🎓 Lessons - {}/{} passed", passed, LESSONS.len()))
+        .style(Style::default().fg(app.config.theme.accent()))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        );
+    f.render_widget(title, chunks[0]);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
+
+    let progress = &app.lesson_progress;
+    let lesson_items: Vec<ListItem> = LESSONS
         .iter()
         .enumerate()
-        .map(|(i, mode)| {
-            let style = if i == app.selected_mode_index {
+        .map(|(i, lesson)| {
+            let style = if i == app.selected_lesson {
                 Style::default()
                     .fg(app.config.theme.highlight())
                     .add_modifier(Modifier::BOLD)
+            } else if !progress.is_unlocked(i) {
+                Style::default().fg(app.config.theme.muted())
+            } else if progress.is_passed(i) {
+                Style::default().fg(app.config.theme.correct())
             } else {
                 Style::default().fg(app.config.theme.text())
             };
-            
-            let indicator = if i == app.selected_mode_index { "► " } else { "  " };
-            let display = format!("{}{}", indicator, mode.display_name());
-            
-            ListItem::new(display).style(style)
+
+            let status = if progress.is_passed(i) {
+                "✓"
+            } else if progress.is_unlocked(i) {
+                "•"
+            } else {
+                "🔒"
+            };
+            ListItem::new(format!("{} {}. {}", status, i + 1, lesson.name)).style(style)
         })
         .collect();
 
-    let mode_list = List::new(mode_items)
+    let lesson_list = List::new(lesson_items)
         .block(
             Block::default()
-                .title("Available Modes")
+                .title("Course")
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(app.config.theme.border())),
         )
         .style(Style::default().fg(app.config.theme.text()));
+    f.render_widget(lesson_list, main_chunks[0]);
 
-    f.render_widget(mode_list, chunks[1]);
+    let lesson = &LESSONS[app.selected_lesson];
+    let unlocked: String = Lesson::unlocked_keys(app.selected_lesson).into_iter().collect();
+    let mut details = vec![
+        Line::from(vec![
+            Span::styled("New Keys: ", Style::default().fg(app.config.theme.text())),
+            Span::styled(lesson.new_keys, Style::default().fg(app.config.theme.accent())),
+        ]),
+        Line::from(vec![
+            Span::styled("All Keys: ", Style::default().fg(app.config.theme.text())),
+            Span::styled(unlocked, Style::default().fg(app.config.theme.text())),
+        ]),
+        Line::from(vec![
+            Span::styled("To Pass: ", Style::default().fg(app.config.theme.text())),
+            Span::styled(lesson.get_requirements(), Style::default().fg(app.config.theme.text())),
+        ]),
+    ];
+    if let Some(record) = progress.get_record(app.selected_lesson) {
+        details.push(Line::from(""));
+        details.push(Line::from(format!("Attempts: {}", record.attempts)));
+        details.push(Line::from(format!(
+            "Best: {:.0} WPM, {:.1}% accuracy",
+            record.best_wpm,
+            record.best_accuracy * 100.0
+        )));
+        if let Some(passed_at) = record.passed_at {
+            details.push(Line::from(Span::styled(
+                format!("Passed {}", passed_at.format("%Y-%m-%d")),
+                Style::default().fg(app.config.theme.correct()),
+            )));
+        }
+    }
 
-    // Instructions
-    let instructions = Paragraph::new("↑/↓ to navigate, Enter to select, Esc/M to return to menu")
+    let details_panel = Paragraph::new(details)
+        .block(
+            Block::default()
+                .title("Lesson")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        )
+        .style(Style::default().fg(app.config.theme.text()))
+        .wrap(Wrap { trim: true });
+    f.render_widget(details_panel, main_chunks[1]);
+
+    let (footer_text, footer_color) = match &app.status_message {
+        Some(message) => (message.as_str(), app.config.theme.error()),
+        None => ("↑/↓ to choose a lesson, Enter to start, Esc/M to return to menu", app.config.theme.muted()),
+    };
+    let instructions = Paragraph::new(footer_text)
+        .style(Style::default().fg(footer_color))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        );
+    f.render_widget(instructions, chunks[2]);
+}
+
+fn draw_daily(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0), Constraint::Length(3)])
+        .split(f.size());
+
+    let today = daily::today();
+    let title = Paragraph::new(format!("📅 Daily Challenge - {}", today.format("%Y-%m-%d")))
+        .style(Style::default().fg(app.config.theme.accent()))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        );
+    f.render_widget(title, chunks[0]);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
+
+    let official = app.history.get_daily_result(today);
+    let mut details = vec![
+        Line::from(format!("{} words, the same for everyone today", daily::DAILY_WORDS)),
+        Line::from("Stop on error, memory, blind and failure settings don't apply"),
+        Line::from(vec![
+            Span::styled("Streak: ", Style::default().fg(app.config.theme.text())),
+            Span::styled(
+                format!("{} days", app.history.get_daily_streak(today)),
+                Style::default().fg(app.config.theme.accent()),
+            ),
+        ]),
+        Line::from(""),
+    ];
+    match official {
+        Some(result) => {
+            details.push(Line::from(Span::styled(
+                format!("Today: {:.0} WPM, {:.1}% accuracy", result.wpm, result.accuracy * 100.0),
+                Style::default().fg(app.config.theme.correct()),
+            )));
+            details.push(Line::from("Further attempts today are practice only."));
+        }
+        None => details.push(Line::from("Your first attempt today is the official one, and starting it uses it up.")),
+    }
+
+    let details_panel = Paragraph::new(details)
+        .block(
+            Block::default()
+                .title("Today")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        )
+        .style(Style::default().fg(app.config.theme.text()))
+        .wrap(Wrap { trim: true });
+    f.render_widget(details_panel, main_chunks[0]);
+
+    let score_items: Vec<ListItem> = app
+        .history
+        .get_daily_results()
+        .iter()
+        .filter_map(|result| {
+            let date = result.daily?;
+            let style = if result.failed {
+                Style::default().fg(app.config.theme.muted())
+            } else {
+                Style::default().fg(app.config.theme.text())
+            };
+            let content = format!(
+                "{} | WPM: {:.0} | Acc: {:.1}%{}",
+                date.format("%Y-%m-%d"),
+                result.wpm,
+                result.accuracy * 100.0,
+                if result.failed { " | FAILED" } else { "" }
+            );
+            Some(ListItem::new(content).style(style))
+        })
+        .collect();
+    let score_list = List::new(score_items)
+        .block(
+            Block::default()
+                .title("Daily Scores")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.config.theme.border())),
+        )
+        .style(Style::default().fg(app.config.theme.text()));
+    f.render_widget(score_list, main_chunks[1]);
+
+    let footer = if official.is_some() {
+        "Enter to practice today's challenge, Esc/M to return to menu"
+    } else {
+        "Enter to start today's challenge, Esc/M to return to menu"
+    };
+    let instructions = Paragraph::new(footer)
         .style(Style::default().fg(app.config.theme.muted()))
         .alignment(Alignment::Center)
         .block(
@@ -183,7 +611,10 @@ fn draw_test(f: &mut Frame, app: &mut App) {
             .split(f.size());
 
         // Title with mode
-        let title = format!("Typing Test - {}", app.current_mode.display_name());
+        let mut title = format!("Typing Test - {}", test.get_mode().display_name());
+        if let (Some(language), Some(source)) = (test.get_language(), test.get_source()) {
+            title.push_str(&format!(" - {} ({})", source, language.display_name()));
+        }
         let title_widget = Paragraph::new(title)
             .style(Style::default().fg(app.config.theme.accent()))
             .alignment(Alignment::Center)
@@ -198,11 +629,23 @@ fn draw_test(f: &mut Frame, app: &mut App) {
         draw_test_progress(f, chunks[1], app, test);
 
         // Text area
-        draw_text_area(f, chunks[2], app, test);
+        let text_area = chunks[2];
+        app.line_layout.update(
+            test,
+            app.input_handler.get_typed_words_input(),
+            app.input_handler.get_current_word_index(),
+            text_area.width.saturating_sub(2) as usize,
+            text_area.height.saturating_sub(2) as usize,
+        );
+        draw_text_area(f, text_area, app, test);
 
         // Instructions
-        let instructions = Paragraph::new("Type the text above. Press Esc to return to menu.")
-            .style(Style::default().fg(app.config.theme.muted()))
+        let (instructions_text, instructions_color) = match &app.status_message {
+            Some(message) => (message.as_str(), app.config.theme.error()),
+            None => ("Type the text above. Press Esc to return to menu.", app.config.theme.muted()),
+        };
+        let instructions = Paragraph::new(instructions_text)
+            .style(Style::default().fg(instructions_color))
             .alignment(Alignment::Center)
             .block(
                 Block::default()
@@ -220,7 +663,7 @@ fn draw_test_progress(f: &mut Frame, area: Rect, app: &App, test: &crate::test::
         .split(area);
 
     // Progress bar
-    let (progress, progress_label) = match &app.current_mode {
+    let (progress, progress_label) = match test.get_mode() {
         crate::app::TestMode::Timed(duration) => {
             let elapsed = test.elapsed_time().as_secs() as f64;
             let total = *duration as f64;
@@ -228,13 +671,19 @@ fn draw_test_progress(f: &mut Frame, area: Rect, app: &App, test: &crate::test::
             let remaining = (total - elapsed).max(0.0) as u32;
             (ratio, format!("Time: {}s", remaining))
         }
-        crate::app::TestMode::WordCount(target) => {
+        crate::app::TestMode::WordCount(_)
+        | crate::app::TestMode::WeakKeys(_)
+        | crate::app::TestMode::NgramDrill(_)
+        | crate::app::TestMode::LetterUnlock
+        | crate::app::TestMode::Lesson(_)
+        | crate::app::TestMode::Daily(_) => {
             let typed = app.input_handler.get_typed_words();
-            let ratio = (typed as f64 / *target as f64).min(1.0);
+            let target = test.get_word_count();
+            let ratio = (typed as f64 / target as f64).min(1.0);
             (ratio, format!("Words: {}/{}", typed, target))
         }
-        crate::app::TestMode::Text(_) => {
-            let progress = app.input_handler.get_progress(&test.get_text());
+        crate::app::TestMode::Text(_) | crate::app::TestMode::Quote(_) | crate::app::TestMode::Code(_) => {
+            let progress = app.input_handler.get_progress(test);
             (progress, format!("Progress: {:.1}%", progress * 100.0))
         }
     };
@@ -253,10 +702,24 @@ fn draw_test_progress(f: &mut Frame, area: Rect, app: &App, test: &crate::test::
 
     // Live stats
     let stats = app.input_handler.get_live_stats(test.elapsed_time());
-    let stats_text = vec![
-        Line::from(format!("WPM: {:.0}", stats.wpm)),
-        Line::from(format!("Accuracy: {:.1}%", stats.accuracy * 100.0)),
-    ];
+    let live_stats = if test.is_blind() && app.config.test_settings.blind_hide_accuracy {
+        format!("WPM: {:.0}", stats.wpm)
+    } else {
+        format!("WPM: {:.0} | Acc: {:.1}%", stats.wpm, stats.accuracy * 100.0)
+    };
+    let mut stats_spans = vec![Span::raw(live_stats)];
+    if let Some(caret) = &app.pace_caret {
+        let pace_chars = caret.chars_at(test.elapsed_time()) as i64;
+        let lead = app.input_handler.get_position_chars(test) as i64 - pace_chars;
+        let (text, color) = match lead {
+            0 => ("on pace".to_string(), app.config.theme.text()),
+            lead if lead > 0 => (format!("ahead by {}", lead), app.config.theme.correct()),
+            lead => (format!("behind by {}", -lead), app.config.theme.error()),
+        };
+        stats_spans.push(Span::raw(format!(" | {} {:.0}: ", caret.get_label(), caret.get_wpm())));
+        stats_spans.push(Span::styled(text, Style::default().fg(color)));
+    }
+    let stats_text = Line::from(stats_spans);
 
     let stats_widget = Paragraph::new(stats_text)
         .style(Style::default().fg(app.config.theme.text()))
@@ -271,47 +734,253 @@ fn draw_test_progress(f: &mut Frame, area: Rect, app: &App, test: &crate::test::
 }
 
 fn draw_text_area(f: &mut Frame, area: Rect, app: &App, test: &crate::test::Test) {
-    let text = test.get_text();
-    let typed = app.input_handler.get_typed_text();
-    let current_pos = typed.chars().count();
-    let mut spans = Vec::new();
+    let theme = &app.config.theme;
+    let typed_words = app.input_handler.get_typed_words_input();
+    let current_word = app.input_handler.get_current_word_index();
 
-    let text_chars: Vec<char> = text.chars().collect();
-    let typed_chars: Vec<char> = typed.chars().collect();
+    // Lay the words out ourselves so only the lines around the cursor get
+    // styled spans, however long an endless test's text has grown.
+    let visible_lines = area.height.saturating_sub(2) as usize;
+    let line_ranges = app.line_layout.lines();
+    let current_line = line_ranges.partition_point(|range| range.start <= current_word).saturating_sub(1);
+    let first_line = current_line.saturating_sub(1);
+    let pace_location = app
+        .pace_caret
+        .as_ref()
+        .filter(|_| test.is_started())
+        .and_then(|caret| pace::locate(test.get_words(), caret.chars_at(test.elapsed_time())));
 
-    for (i, &ch) in text_chars.iter().enumerate() {
-        let style = if i < typed_chars.len() {
-            let typed_char = typed_chars[i];
-            if typed_char == ch {
-                Style::default().fg(app.config.theme.correct())
-            } else {
-                Style::default().fg(app.config.theme.error()).bg(Color::Red)
+    let lines: Vec<Line> = line_ranges[first_line.min(line_ranges.len())..]
+        .iter()
+        .take(visible_lines)
+        .map(|range| {
+            let mut spans = Vec::new();
+            // Skipped indentation is drawn ahead of the line's first word
+            if !range.is_empty() {
+                let indent = expand_tabs(test.get_word_indent(range.start), test.get_tab_width());
+                if !indent.is_empty() {
+                    spans.push(Span::raw(indent));
+                }
             }
-        } else if i == current_pos {
-            Style::default()
-                .fg(app.config.theme.text())
-                .bg(app.config.theme.cursor())
-                .add_modifier(Modifier::UNDERLINED)
-        } else {
-            Style::default().fg(app.config.theme.muted())
-        };
-
-        spans.push(Span::styled(ch.to_string(), style));
-    }
+            spans.extend(range.clone().flat_map(|i| {
+                let pace_offset = pace_location.filter(|(word, _)| *word == i).map(|(_, offset)| offset);
+                word_spans(app, test, typed_words.get(i), i, current_word, pace_offset)
+            }));
+            Line::from(spans)
+        })
+        .collect();
 
-    let text_paragraph = Paragraph::new(Line::from(spans))
+    let title = match test.memory_time_left() {
+        Some(left) => format!("Text to Type - memorize: {}s", left.as_secs() + 1),
+        None => "Text to Type".to_string(),
+    };
+    let text_paragraph = Paragraph::new(lines)
         .block(
             Block::default()
-                .title("Text to Type")
+                .title(title)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(app.config.theme.border())),
+                .border_style(Style::default().fg(theme.border())),
         )
-        .wrap(Wrap { trim: false })
         .alignment(Alignment::Left);
 
     f.render_widget(text_paragraph, area);
 }
 
+/// The range of words on each displayed line, wrapping long lines and
+/// breaking after words that end a line of code. Blank lines are empty ranges.
+///
+/// Kept between frames: only lines from the first one whose typed input
+/// changed are redone, and only as far as the screen needs.
+#[derive(Debug, Default)]
+pub struct LineLayout {
+    width: usize,
+    lines: Vec<Range<usize>>,
+    // First word not in a finished line; an unfinished last line is kept too
+    next_word: usize,
+    open_line: bool,
+    typed_words: Vec<String>,
+}
+
+impl LineLayout {
+    pub fn lines(&self) -> &[Range<usize>] {
+        &self.lines
+    }
+
+    pub fn update(
+        &mut self,
+        test: &crate::test::Test,
+        typed_words: &[String],
+        current_word: usize,
+        width: usize,
+        visible_lines: usize,
+    ) {
+        if width != self.width {
+            *self = LineLayout { width, ..LineLayout::default() };
+        }
+        if self.open_line {
+            self.lines.pop();
+            self.open_line = false;
+        }
+
+        // Typed input wider than its word widens the word, reflowing from its line on
+        let changed = (0..typed_words.len().max(self.typed_words.len()))
+            .find(|&i| typed_words.get(i) != self.typed_words.get(i));
+        if let Some(changed) = changed {
+            self.typed_words.truncate(changed);
+            self.typed_words.extend_from_slice(&typed_words[changed.min(typed_words.len())..]);
+            while self.lines.last().is_some_and(|line| line.end >= changed) {
+                self.lines.pop();
+            }
+            self.next_word = self.lines.last().map_or(0, |line| line.end);
+        }
+
+        let words = test.get_words();
+        let tab_width = test.get_tab_width();
+        let char_width = |text: &str| display_width(&expand_tabs(text, tab_width));
+        let is_enough = |lines: &[Range<usize>], next_word: usize| {
+            next_word > current_word && lines.len() >= lines.partition_point(|line| line.start <= current_word) + visible_lines
+        };
+        if is_enough(&self.lines, self.next_word) {
+            return;
+        }
+
+        let mut start = self.next_word;
+        let mut line_width = 0;
+        for (i, word) in words.iter().enumerate().skip(start) {
+            let typed_width = typed_words.get(i).map_or(0, |w| char_width(w));
+            let word_width = char_width(test.get_word_indent(i)) + char_width(word).max(typed_width) + 1;
+            if line_width > 0 && line_width + word_width > width {
+                self.lines.push(start..i);
+                self.next_word = i;
+                if is_enough(&self.lines, self.next_word) {
+                    return;
+                }
+                start = i;
+                line_width = 0;
+            }
+            line_width += word_width;
+
+            if let WordBreak::Newline { blank_lines } = test.get_word_break(i) {
+                self.lines.push(start..i + 1);
+                self.lines.extend(std::iter::repeat_n(i + 1..i + 1, blank_lines));
+                self.next_word = i + 1;
+                if is_enough(&self.lines, self.next_word) {
+                    return;
+                }
+                start = i + 1;
+                line_width = 0;
+            }
+        }
+        if start < words.len() {
+            self.lines.push(start..words.len());
+            self.open_line = true;
+        }
+    }
+}
+
+// Stands in for each column of a word hidden in memory mode
+const MEMORY_MASK: &str = "·";
+
+fn expand_tabs(text: &str, tab_width: usize) -> String {
+    text.replace('\t', &" ".repeat(tab_width))
+}
+
+fn word_spans<'a>(
+    app: &App,
+    test: &crate::test::Test,
+    typed: Option<&String>,
+    index: usize,
+    current_word: usize,
+    pace_offset: Option<usize>,
+) -> Vec<Span<'a>> {
+    let theme = &app.config.theme;
+    let word = &test.get_words()[index];
+    let syntax = test.get_word_syntax(index);
+    let cursor_style = Style::default()
+        .fg(theme.text())
+        .bg(theme.cursor())
+        .add_modifier(Modifier::UNDERLINED);
+
+    // Compared a grapheme cluster at a time, so accents and emoji stay whole
+    let target_graphemes: Vec<&str> = word.graphemes(true).collect();
+    let typed_graphemes: Vec<&str> = typed.map(|w| w.graphemes(true).collect()).unwrap_or_default();
+    let is_current = index == current_word;
+    let len = target_graphemes.len().max(typed_graphemes.len());
+    // The pace caret yields to the real cursor when they overlap
+    let pace_style = Style::default().fg(theme.background()).bg(theme.highlight());
+    // A cluster still being built up, like a letter waiting for its accent, keeps the cursor
+    let ascii_folding = test.get_ascii_folding();
+    let in_progress = typed_graphemes.last().is_some_and(|&last| {
+        target_graphemes
+            .get(typed_graphemes.len() - 1)
+            .is_some_and(|&target| compare_graphemes(last, target, ascii_folding) == GraphemeMatch::Partial)
+    });
+    let cursor_at = is_current.then(|| typed_graphemes.len() - usize::from(in_progress));
+    // In memory mode a hidden word is masked, apart from what's being typed into it
+    let hidden = test.is_word_hidden(index, current_word);
+    let blind = test.is_blind();
+    let mut spans = Vec::with_capacity(len + 1);
+
+    for j in 0..len {
+        let (grapheme, style) = match (target_graphemes.get(j), typed_graphemes.get(j)) {
+            // Blind runs show typed chars alike, right or wrong
+            (target, Some(&typed)) if blind && cursor_at != Some(j) => {
+                (target.copied().unwrap_or(typed), Style::default().fg(theme.text()))
+            }
+            (Some(&target), Some(&typed)) if compare_graphemes(typed, target, ascii_folding) == GraphemeMatch::Exact => {
+                (target, Style::default().fg(theme.correct()))
+            }
+            (Some(&target), _) if cursor_at == Some(j) => (target, cursor_style),
+            (Some(&target), Some(_)) => {
+                (target, Style::default().fg(theme.error()).bg(Color::Red))
+            }
+            // Overtyped chars are shown inline after the word
+            (None, Some(&typed)) => (
+                typed,
+                Style::default().fg(theme.error()).add_modifier(Modifier::CROSSED_OUT),
+            ),
+            // Chars skipped over by submitting the word early
+            (Some(&target), None) if index < current_word && !blind => (
+                target,
+                Style::default().fg(theme.error()).add_modifier(Modifier::UNDERLINED),
+            ),
+            (Some(&target), None) => {
+                let color = syntax.get(j).and_then(|&kind| theme.syntax(kind)).unwrap_or(theme.muted());
+                (target, Style::default().fg(color))
+            }
+            (None, None) => unreachable!(),
+        };
+        let style = if pace_offset == Some(j) && j < target_graphemes.len() && cursor_at != Some(j) {
+            pace_style
+        } else {
+            style
+        };
+        let text = expand_tabs(grapheme, test.get_tab_width());
+        let (text, style) = match typed_graphemes.get(j) {
+            _ if !hidden => (text, style),
+            Some(&typed) if is_current => (expand_tabs(typed, test.get_tab_width()), style),
+            _ if cursor_at == Some(j) => (MEMORY_MASK.repeat(display_width(&text)), style),
+            _ => (MEMORY_MASK.repeat(display_width(&text)), Style::default().fg(theme.muted())),
+        };
+        spans.push(Span::styled(text, style));
+    }
+
+    // The separator shows where Space or Enter is due
+    let (separator, separator_style) = match test.get_word_break(index) {
+        WordBreak::Space => (" ", Style::default()),
+        WordBreak::Newline { .. } => ("↵", Style::default().fg(theme.muted())),
+    };
+    let separator_style = if cursor_at.is_some_and(|at| at >= target_graphemes.len()) {
+        cursor_style
+    } else if pace_offset == Some(target_graphemes.len()) {
+        pace_style
+    } else {
+        separator_style
+    };
+    spans.push(Span::styled(separator, separator_style));
+    spans
+}
+
 fn draw_results(f: &mut Frame, app: &App) {
     if let Some(stats) = &app.last_stats {
         let chunks = Layout::default()
@@ -323,20 +992,44 @@ fn draw_results(f: &mut Frame, app: &App) {
             ])
             .split(f.size());
 
-        let title = Paragraph::new("🎉 Test Results")
-            .style(Style::default().fg(app.config.theme.accent()))
+        // Failed runs get a red banner with the reason instead of the usual title
+        let (title_text, title_color, border_color) = match &stats.fail_reason {
+            Some(reason) if stats.failed => (
+                format!("💀 Test Failed - {}", reason),
+                app.config.theme.error(),
+                app.config.theme.error(),
+            ),
+            _ if stats.failed => ("💀 Test Failed".to_string(), app.config.theme.error(), app.config.theme.error()),
+            _ => ("🎉 Test Results".to_string(), app.config.theme.accent(), app.config.theme.border()),
+        };
+        let title = Paragraph::new(title_text)
+            .style(Style::default().fg(title_color).add_modifier(Modifier::BOLD))
             .alignment(Alignment::Center)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(app.config.theme.border())),
+                    .border_style(Style::default().fg(border_color)),
             );
         f.render_widget(title, chunks[0]);
 
+        // Blind runs reveal what was typed right and wrong below the stats
+        let blind_test = app.last_test.as_ref().filter(|test| test.is_blind());
+        let body_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(if blind_test.is_some() {
+                [Constraint::Percentage(60), Constraint::Percentage(40)]
+            } else {
+                [Constraint::Percentage(100), Constraint::Percentage(0)]
+            })
+            .split(chunks[1]);
+        if let Some(test) = blind_test {
+            draw_blind_reveal(f, body_chunks[1], app, test);
+        }
+
         let main_chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(chunks[1]);
+            .split(body_chunks[0]);
 
         let primary_stats = vec![
             Line::from(vec![
@@ -344,7 +1037,7 @@ fn draw_results(f: &mut Frame, app: &App) {
                 Span::styled(
                     format!("{:.0}", stats.wpm),
                     Style::default()
-                        .fg(app.config.theme.accent())
+                        .fg(if stats.failed { app.config.theme.muted() } else { app.config.theme.accent() })
                         .add_modifier(Modifier::BOLD),
                 ),
             ]),
@@ -382,12 +1075,19 @@ fn draw_results(f: &mut Frame, app: &App) {
                     Style::default().fg(app.config.theme.text()),
                 ),
             ]),
+            Line::from(vec![
+                Span::styled("Extra/Missed: ", Style::default().fg(app.config.theme.text())),
+                Span::styled(
+                    format!("{}/{}", stats.extra_chars, stats.missed_chars),
+                    Style::default().fg(app.config.theme.text()),
+                ),
+            ]),
         ];
 
         let primary_panel = Paragraph::new(primary_stats)
             .block(
                 Block::default()
-                    .title("Statistics")
+                    .title(if stats.failed { "Statistics (at failure)" } else { "Statistics" })
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(app.config.theme.border())),
             )
@@ -409,14 +1109,44 @@ fn draw_results(f: &mut Frame, app: &App) {
             Line::from(vec![
                 Span::styled("Mode: ", Style::default().fg(app.config.theme.text())),
                 Span::styled(
-                    app.current_mode.display_name(),
+                    stats.test_mode.clone(),
                     Style::default().fg(app.config.theme.accent()),
                 ),
             ]),
-            Line::from(""),
-            Line::from("Most Common Errors:"),
         ];
         let mut info_lines = additional_info;
+        if let Some(word_source) = &stats.word_source {
+            info_lines.push(Line::from(vec![
+                Span::styled("Words: ", Style::default().fg(app.config.theme.text())),
+                Span::styled(word_source.display_name().to_string(), Style::default().fg(app.config.theme.text())),
+            ]));
+        }
+        if !stats.transforms.is_empty() {
+            info_lines.push(Line::from(vec![
+                Span::styled("Text: ", Style::default().fg(app.config.theme.text())),
+                Span::styled(stats.transforms.get_summary(), Style::default().fg(app.config.theme.text())),
+            ]));
+        }
+        if stats.error_policy != ErrorPolicy::Off {
+            info_lines.push(Line::from(vec![
+                Span::styled("Stop on Error: ", Style::default().fg(app.config.theme.text())),
+                Span::styled(stats.error_policy.display_name(), Style::default().fg(app.config.theme.text())),
+            ]));
+        }
+        if let Some(seed) = stats.seed {
+            info_lines.push(Line::from(vec![
+                Span::styled("Seed: ", Style::default().fg(app.config.theme.text())),
+                Span::styled(seed.to_string(), Style::default().fg(app.config.theme.muted())),
+            ]));
+        }
+        if let Some(source) = &stats.quote_source {
+            info_lines.push(Line::from(vec![
+                Span::styled("Source: ", Style::default().fg(app.config.theme.text())),
+                Span::styled(source.clone(), Style::default().fg(app.config.theme.highlight())),
+            ]));
+        }
+        info_lines.push(Line::from(""));
+        info_lines.push(Line::from("Most Common Errors:"));
         let mut error_list: Vec<_> = stats.error_frequency.iter().collect();
         error_list.sort_by_key(|&(_, &count)| std::cmp::Reverse(count));
 
@@ -426,6 +1156,94 @@ fn draw_results(f: &mut Frame, app: &App) {
             }
         }
 
+        if let Some(report) = &app.weak_key_report {
+            info_lines.push(Line::from(""));
+            info_lines.push(Line::from("Weak Key Error Rates:"));
+            for key in report {
+                let color = match key.after {
+                    Some(after) if after < key.before => app.config.theme.correct(),
+                    Some(after) if after > key.before => app.config.theme.error(),
+                    _ => app.config.theme.muted(),
+                };
+                info_lines.push(Line::from(Span::styled(
+                    format!("  {}", key.get_summary()),
+                    Style::default().fg(color),
+                )));
+            }
+        }
+
+        if !app.ngram_report.is_empty() {
+            info_lines.push(Line::from(""));
+            info_lines.push(Line::from("Slowest N-grams:"));
+            for score in &app.ngram_report {
+                let color = if score.error_rate > 0.0 { app.config.theme.error() } else { app.config.theme.text() };
+                info_lines.push(Line::from(Span::styled(
+                    format!("  {}", score.get_summary()),
+                    Style::default().fg(color),
+                )));
+            }
+        }
+
+        if let Some(report) = &app.unlock_report {
+            info_lines.push(Line::from(""));
+            let letters: String = report.letters.iter().collect();
+            info_lines.push(Line::from(format!("Unlocked Letters: {}", letters)));
+            if let Some(letter) = report.new_letter {
+                info_lines.push(Line::from(Span::styled(
+                    format!("  New letter unlocked: {}", letter),
+                    Style::default().fg(app.config.theme.correct()),
+                )));
+            } else if !report.below_target.is_empty() {
+                let below: Vec<String> = report.below_target.iter().map(|ch| ch.to_string()).collect();
+                info_lines.push(Line::from(Span::styled(
+                    format!("  Below target: {}", below.join(" ")),
+                    Style::default().fg(app.config.theme.muted()),
+                )));
+            }
+        }
+
+        if let (Some(passed), Some(TestMode::Lesson(index))) = (app.lesson_passed, &app.last_mode) {
+            let lesson = &LESSONS[*index];
+            info_lines.push(Line::from(""));
+            let (summary, color) = if passed {
+                let next = match LESSONS.get(index + 1) {
+                    Some(next) => format!(" - {} unlocked", next.name),
+                    None => " - course complete".to_string(),
+                };
+                (format!("Lesson passed{}", next), app.config.theme.correct())
+            } else {
+                (format!("Lesson not passed - needs {}", lesson.get_requirements()), app.config.theme.error())
+            };
+            info_lines.push(Line::from(Span::styled(summary, Style::default().fg(color))));
+        }
+
+        if let Some(TestMode::Daily(date)) = &app.last_mode {
+            info_lines.push(Line::from(""));
+            let (summary, color) = if stats.daily.is_some() {
+                let streak = app.history.get_daily_streak(*date);
+                (format!("Official daily attempt - {} day streak", streak), app.config.theme.correct())
+            } else {
+                ("Practice run - the official attempt is already recorded".to_string(), app.config.theme.muted())
+            };
+            info_lines.push(Line::from(Span::styled(summary, Style::default().fg(color))));
+        }
+
+        if let Some(splits) = &app.ghost_report {
+            info_lines.push(Line::from(""));
+            info_lines.push(Line::from("Vs Ghost:"));
+            for split in splits {
+                let color = match split.delta_secs {
+                    delta if delta < 0.0 => app.config.theme.correct(),
+                    delta if delta > 0.0 => app.config.theme.error(),
+                    _ => app.config.theme.muted(),
+                };
+                info_lines.push(Line::from(Span::styled(
+                    format!("  {}", split.get_summary()),
+                    Style::default().fg(color),
+                )));
+            }
+        }
+
         let additional_panel = Paragraph::new(info_lines)
             .block(
                 Block::default()
@@ -437,8 +1255,17 @@ fn draw_results(f: &mut Frame, app: &App) {
 
         f.render_widget(additional_panel, main_chunks[1]);
 
-        let instructions = Paragraph::new("Press R to restart test, M to return to menu")
-            .style(Style::default().fg(app.config.theme.muted()))
+        let instructions_text = match (stats.failed, app.ngram_report.is_empty()) {
+            (true, _) => "Failed runs are kept in history but never count as a best. Press R to try again, T to retry the same text, M to return to menu",
+            (false, true) => "Press R to restart test, T to retry the same text against your ghost, M to return to menu",
+            (false, false) => "Press R to restart test, T to retry the same text against your ghost, N to drill slow n-grams, M to return to menu",
+        };
+        let (instructions_text, instructions_color) = match &app.status_message {
+            Some(message) => (message.as_str(), app.config.theme.error()),
+            None => (instructions_text, app.config.theme.muted()),
+        };
+        let instructions = Paragraph::new(instructions_text)
+            .style(Style::default().fg(instructions_color))
             .alignment(Alignment::Center)
             .block(
                 Block::default()
@@ -449,6 +1276,36 @@ fn draw_results(f: &mut Frame, app: &App) {
     }
 }
 
+/// Typed words after a blind run, green when right and red, with the expected
+/// word alongside, when wrong.
+fn draw_blind_reveal(f: &mut Frame, area: Rect, app: &App, test: &crate::test::Test) {
+    let theme = &app.config.theme;
+    let input = &app.input_handler;
+    let mut spans = Vec::new();
+    for (i, (typed, target)) in input.get_typed_words_input().iter().zip(test.get_words()).enumerate() {
+        if typed.is_empty() && i == input.get_current_word_index() {
+            break;
+        }
+        if input.is_word_correct(i, test) {
+            spans.push(Span::styled(typed.clone(), Style::default().fg(theme.correct())));
+        } else {
+            spans.push(Span::styled(typed.clone(), Style::default().fg(theme.error()).add_modifier(Modifier::CROSSED_OUT)));
+            spans.push(Span::styled(format!(" ({})", target), Style::default().fg(theme.muted())));
+        }
+        spans.push(Span::raw(" "));
+    }
+
+    let reveal = Paragraph::new(Line::from(spans))
+        .block(
+            Block::default()
+                .title("Blind Run Review")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border())),
+        )
+        .wrap(Wrap { trim: true });
+    f.render_widget(reveal, area);
+}
+
 fn draw_history(f: &mut Frame, app: &mut App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -469,8 +1326,8 @@ fn draw_history(f: &mut Frame, app: &mut App) {
         );
     f.render_widget(title, chunks[0]);
 
-    let results = app.history.get_results();
-    if results.is_empty() {
+    let results = app.history.get_filtered_results(app.history_mode_filter.as_deref());
+    if app.history.get_results().is_empty() {
         let empty_msg = Paragraph::new("No test results yet. Complete a test to see your history!")
             .style(Style::default().fg(app.config.theme.muted()))
             .alignment(Alignment::Center)
@@ -493,18 +1350,22 @@ fn draw_history(f: &mut Frame, app: &mut App) {
                     Style::default().fg(app.config.theme.text())
                 };
 
+                let style = if result.failed && i != app.selected_history_item {
+                    style.fg(app.config.theme.muted())
+                } else {
+                    style
+                };
+
                 let date_str = result.timestamp.format("%Y-%m-%d %H:%M").to_string();
                 let content = format!(
-                    "{} | WPM: {:.0} | Acc: {:.1}% | Mode: {}",
+                    "{} | WPM: {:.0} | Acc: {:.1}% | Mode: {}{}",
                     date_str,
                     result.wpm,
                     result.accuracy * 100.0,
-                    match result.test_mode.as_str() {
-                        mode if mode.starts_with("Timed") => mode,
-                        mode if mode.starts_with("WordCount") => mode,
-                        _ => "Custom",
-                    }
+                    result.test_mode,
+                    if result.failed { " | FAILED" } else { "" }
                 );
+                let content = if result.daily.is_some() { format!("{} | OFFICIAL", content) } else { content };
 
                 ListItem::new(content).style(style)
             })
@@ -513,7 +1374,7 @@ fn draw_history(f: &mut Frame, app: &mut App) {
         let history_list = List::new(history_items)
             .block(
                 Block::default()
-                    .title(format!("History ({} tests)", results.len()))
+                    .title(history_title(app))
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(app.config.theme.border())),
             )
@@ -522,7 +1383,7 @@ fn draw_history(f: &mut Frame, app: &mut App) {
         f.render_widget(history_list, chunks[1]);
     }
 
-    let instructions = Paragraph::new("↑/↓ to navigate, M or Esc to return to menu")
+    let instructions = Paragraph::new("↑/↓ to navigate, ←/→ to filter by mode, F to include/exclude failed runs in averages, M or Esc to return to menu")
         .style(Style::default().fg(app.config.theme.muted()))
         .alignment(Alignment::Center)
         .block(
@@ -531,4 +1392,24 @@ fn draw_history(f: &mut Frame, app: &mut App) {
                 .border_style(Style::default().fg(app.config.theme.border())),
         );
     f.render_widget(instructions, chunks[2]);
-}
\ No newline at end of file
+}
+
+fn history_title(app: &App) -> String {
+    let include_failed = app.config.test_settings.include_failed_in_averages;
+    let mode = app.history_mode_filter.as_deref();
+    let history = &app.history;
+    let failed = history.get_failed_count(mode);
+    let failed_note = match (failed, include_failed) {
+        (0, _) => String::new(),
+        (_, true) => format!(", {} failed included", failed),
+        (_, false) => format!(", {} failed excluded", failed),
+    };
+    format!(
+        "History - {} ({} tests) - Avg WPM: {:.0} | Avg Acc: {:.1}%{}",
+        mode.unwrap_or("All modes"),
+        history.get_filtered_results(mode).len(),
+        history.get_average_wpm(include_failed, mode),
+        history.get_average_accuracy(include_failed, mode) * 100.0,
+        failed_note
+    )
+}
@@ -1,6 +1,13 @@
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::key_profile::WordBias;
 
 pub const COMMON_WORDS: &[&str] = &[
     "the", "be", "to", "of", "and", "a", "in", "that", "have", "i",
@@ -90,11 +97,78 @@ pub enum WordDifficulty {
     Programming,
 }
 
-pub fn generate_random_words(count: usize) -> String {
-    generate_words_with_difficulty(count, WordDifficulty::Easy)
+/// Where generated tests get their words from, independent of the test mode.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum WordSource {
+    #[default]
+    Easy,
+    Medium,
+    Hard,
+    Programming,
+    Numbers,
+    Symbols,
+    Mixed,
+    Pangrams,
+    /// A bundled language or a list from the `wordlists` config folder, by name
+    List(String),
+}
+
+impl WordSource {
+    pub fn all() -> Vec<WordSource> {
+        vec![
+            WordSource::Easy,
+            WordSource::Medium,
+            WordSource::Hard,
+            WordSource::Programming,
+            WordSource::Numbers,
+            WordSource::Symbols,
+            WordSource::Mixed,
+            WordSource::Pangrams,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            WordSource::Easy => "Easy words",
+            WordSource::Medium => "Medium words",
+            WordSource::Hard => "Hard words",
+            WordSource::Programming => "Programming words",
+            WordSource::Numbers => "Numbers",
+            WordSource::Symbols => "Symbols",
+            WordSource::Mixed => "Mixed",
+            WordSource::Pangrams => "Pangrams",
+            WordSource::List(name) => name,
+        }
+    }
+}
+
+/// Generates exactly `count` whitespace-separated words from `source`.
+/// Named lists have to be loaded first (see `WordList::generate`), so they fall
+/// back to easy words here.
+pub fn generate_from_source(source: &WordSource, count: usize, bias: Option<&WordBias>, rng: &mut impl Rng) -> String {
+    match source {
+        WordSource::Easy => generate_words_with_difficulty(count, WordDifficulty::Easy, bias, rng),
+        WordSource::Medium => generate_words_with_difficulty(count, WordDifficulty::Medium, bias, rng),
+        WordSource::Hard => generate_words_with_difficulty(count, WordDifficulty::Hard, bias, rng),
+        WordSource::Programming => generate_words_with_difficulty(count, WordDifficulty::Programming, bias, rng),
+        WordSource::Numbers => generate_number_practice(count, rng),
+        WordSource::Symbols => generate_symbol_practice(count, rng),
+        WordSource::Mixed => generate_mixed_content(count, true, true, rng),
+        WordSource::Pangrams => {
+            // Every pangram has at least five words
+            let text = generate_pangram_text(count / 5 + 1, rng);
+            text.split_whitespace().take(count).collect::<Vec<_>>().join(" ")
+        }
+        WordSource::List(_) => generate_random_words(count, rng),
+    }
+}
+
+pub fn generate_random_words(count: usize, rng: &mut impl Rng) -> String {
+    generate_words_with_difficulty(count, WordDifficulty::Easy, None, rng)
 }
 
-pub fn generate_words_with_difficulty(count: usize, difficulty: WordDifficulty) -> String {
+/// Picks `count` words of the given difficulty, favouring words with weak keys when a `bias` is given.
+pub fn generate_words_with_difficulty(count: usize, difficulty: WordDifficulty, bias: Option<&WordBias>, rng: &mut impl Rng) -> String {
     let word_list = match difficulty {
         WordDifficulty::Easy => COMMON_WORDS,
         WordDifficulty::Medium => &[COMMON_WORDS, CHALLENGING_WORDS].concat(),
@@ -102,11 +176,19 @@ pub fn generate_words_with_difficulty(count: usize, difficulty: WordDifficulty)
         WordDifficulty::Programming => PROGRAMMING_WORDS,
     };
 
-    let mut rng = thread_rng();
     let mut words = Vec::new();
     
+    if let Some(bias) = bias {
+        for _ in 0..count {
+            if let Ok(&word) = word_list.choose_weighted(rng, |word| bias.word_weight(word)) {
+                words.push(word.to_string());
+            }
+        }
+        return words.join(" ");
+    }
+
     for _ in 0..count {
-        if let Some(&word) = word_list.choose(&mut rng) {
+        if let Some(&word) = word_list.choose(rng) {
             words.push(word.to_string());
         }
     }
@@ -114,7 +196,7 @@ pub fn generate_words_with_difficulty(count: usize, difficulty: WordDifficulty)
     words.join(" ")
 }
 
-pub fn generate_focused_practice(target_chars: &[char], word_count: usize) -> String {
+pub fn generate_focused_practice(target_chars: &[char], word_count: usize, rng: &mut impl Rng) -> String {
     let target_set: HashSet<char> = target_chars.iter().cloned().collect();
     
     let filtered_words: Vec<&str> = COMMON_WORDS.iter().filter(|word| {
@@ -122,22 +204,131 @@ pub fn generate_focused_practice(target_chars: &[char], word_count: usize) -> St
         }).cloned().collect();
 
     if filtered_words.is_empty() {
-        return generate_random_words(word_count);
+        return generate_random_words(word_count, rng);
     }
 
-    let mut rng = thread_rng();
+    // Words that hit the target chars more often come up more often
+    let weights: Vec<usize> = filtered_words
+        .iter()
+        .map(|word| word.chars().filter(|c| target_set.contains(c)).count())
+        .collect();
+    let distribution = WeightedIndex::new(&weights).expect("every filtered word contains a target char");
+
     let mut words = Vec::new();
     
     for _ in 0..word_count {
-        if let Some(&word) = filtered_words.choose(&mut rng) {
-            words.push(word.to_string());
-        }
+        words.push(filtered_words[distribution.sample(rng)].to_string());
     }
     
     words.join(" ")
 }
 
-pub fn generate_pangram_text(repeat_count: usize) -> String {
+/// Generates words dense in `ngrams`, weighted by how many times each word
+/// contains one. Made-up words built from the n-grams fill in when few real
+/// words contain them.
+pub fn generate_ngram_practice(ngrams: &[String], word_count: usize, rng: &mut impl Rng) -> String {
+    if ngrams.is_empty() {
+        return generate_random_words(word_count, rng);
+    }
+
+    let occurrences = |word: &str| ngrams.iter().map(|ngram| word.matches(ngram.as_str()).count()).sum::<usize>();
+    let mut candidates: Vec<&str> = COMMON_WORDS
+        .iter()
+        .chain(CHALLENGING_WORDS)
+        .chain(PROGRAMMING_WORDS)
+        .filter(|word| occurrences(word) > 0)
+        .cloned()
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+    let weights: Vec<usize> = candidates.iter().map(|word| occurrences(word)).collect();
+    let distribution = WeightedIndex::new(&weights).ok();
+
+    let mut words = Vec::new();
+    for _ in 0..word_count {
+        let use_real_word = candidates.len() >= MIN_RESTRICTED_WORDS || rng.gen_ratio(candidates.len() as u32, MIN_RESTRICTED_WORDS as u32);
+        match &distribution {
+            Some(distribution) if use_real_word => words.push(candidates[distribution.sample(rng)].to_string()),
+            _ => {
+                let pieces = rng.gen_range(2..=3);
+                words.push((0..pieces).map(|_| ngrams.choose(rng).expect("ngrams is not empty").as_str()).collect());
+            }
+        }
+    }
+
+    words.join(" ")
+}
+
+/// Generates words typed only with `allowed` keys, favouring ones that use
+/// `focus` keys. Real words are used where the key set allows enough of them;
+/// the rest are made-up letter groups. Focus keys few real words contain, like
+/// digits or symbols, get a fixed share of made-up words so they still come up.
+pub fn generate_restricted_practice(allowed: &[char], focus: &[char], word_count: usize, rng: &mut impl Rng) -> String {
+    let allowed_set: HashSet<char> = allowed.iter().cloned().collect();
+    let focus_set: HashSet<char> = focus.iter().cloned().collect();
+    let mut allowed: Vec<char> = allowed_set.iter().cloned().collect();
+    allowed.sort_unstable();
+    if allowed.is_empty() {
+        return String::new();
+    }
+
+    let mut candidates: Vec<&str> = COMMON_WORDS
+        .iter()
+        .chain(CHALLENGING_WORDS)
+        .chain(PROGRAMMING_WORDS)
+        .filter(|word| word.chars().all(|c| allowed_set.contains(&c)))
+        .cloned()
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+    let weights: Vec<usize> = candidates
+        .iter()
+        .map(|word| 1 + word.chars().filter(|c| focus_set.contains(c)).count())
+        .collect();
+    let distribution = WeightedIndex::new(&weights).ok();
+    let mut rare_focus: Vec<char> = focus_set
+        .iter()
+        .filter(|key| allowed_set.contains(key))
+        .filter(|&&key| candidates.iter().filter(|word| word.contains(key)).count() < MIN_FOCUS_WORDS)
+        .cloned()
+        .collect();
+    rare_focus.sort_unstable();
+
+    let mut words = Vec::new();
+    for i in 0..word_count {
+        if !rare_focus.is_empty() && i % RARE_FOCUS_EVERY == RARE_FOCUS_EVERY - 1 {
+            words.push(made_up_word(&allowed, &rare_focus, rng));
+            continue;
+        }
+        let use_real_word = candidates.len() >= MIN_RESTRICTED_WORDS || rng.gen_ratio(candidates.len() as u32, MIN_RESTRICTED_WORDS as u32);
+        match &distribution {
+            Some(distribution) if use_real_word => words.push(candidates[distribution.sample(rng)].to_string()),
+            _ => words.push(made_up_word(&allowed, focus, rng)),
+        }
+    }
+
+    words.join(" ")
+}
+
+// Below this many real words a restricted drill mixes in made-up ones
+const MIN_RESTRICTED_WORDS: usize = 15;
+// A focus key in fewer real words than this gets every `RARE_FOCUS_EVERY`th word made up around it
+const MIN_FOCUS_WORDS: usize = 3;
+const RARE_FOCUS_EVERY: usize = 3;
+
+fn made_up_word(allowed: &[char], focus: &[char], rng: &mut impl Rng) -> String {
+    let len = rng.gen_range(2..=5);
+    let mut word: Vec<char> = (0..len).map(|_| *allowed.choose(rng).expect("allowed is not empty")).collect();
+    if let Some(&key) = focus.choose(rng)
+        && !word.iter().any(|c| focus.contains(c))
+    {
+        let slot = rng.gen_range(0..len);
+        word[slot] = key;
+    }
+    word.into_iter().collect()
+}
+
+pub fn generate_pangram_text(repeat_count: usize, rng: &mut impl Rng) -> String {
     let pangrams = [
         "The quick brown fox jumps over the lazy dog.",
         "Pack my box with five dozen liquor jugs.",
@@ -148,11 +339,10 @@ pub fn generate_pangram_text(repeat_count: usize) -> String {
         "Quick zephyrs blow, vexing daft Jim."
     ];
 
-    let mut rng = thread_rng();
     let mut result = Vec::new();
     
     for _ in 0..repeat_count {
-        if let Some(&pangram) = pangrams.choose(&mut rng) {
+        if let Some(&pangram) = pangrams.choose(rng) {
             result.push(pangram.to_string());
         }
     }
@@ -160,31 +350,29 @@ pub fn generate_pangram_text(repeat_count: usize) -> String {
     result.join(" ")
 }
 
-pub fn generate_number_practice(count: usize) -> String {
-    let _rng = thread_rng();
+pub fn generate_number_practice(count: usize, rng: &mut impl Rng) -> String {
     let mut parts = Vec::new();
     
     for _ in 0..count {
-        let num = rand::random::<u32>() % 10000;
+        let num = rng.gen_range(0..10000);
         parts.push(num.to_string());
     }
     
     parts.join(" ")
 }
 
-pub fn generate_symbol_practice(count: usize) -> String {
+pub fn generate_symbol_practice(count: usize, rng: &mut impl Rng) -> String {
     let symbols = "!@#$%^&*()_+-=[]{}|;':\",./<>?`~";
     let symbol_chars: Vec<char> = symbols.chars().collect();
-    let mut rng = thread_rng();
     let mut result = String::new();
     
     for i in 0..count {
         if i > 0 {
             result.push(' ');
         }
-        let seq_length = 3 + (rand::random::<usize>() % 5);
+        let seq_length = rng.gen_range(3..8);
         for _ in 0..seq_length {
-            if let Some(&symbol) = symbol_chars.choose(&mut rng) {
+            if let Some(&symbol) = symbol_chars.choose(rng) {
                 result.push(symbol);
             }
         }
@@ -193,26 +381,21 @@ pub fn generate_symbol_practice(count: usize) -> String {
     result
 }
 
-pub fn generate_mixed_content(word_count: usize, include_numbers: bool, include_symbols: bool) -> String {
-    let mut parts = Vec::new();
-    let word_portion = word_count * 70 / 100; // 70% words
+pub fn generate_mixed_content(word_count: usize, include_numbers: bool, include_symbols: bool, rng: &mut impl Rng) -> String {
     let number_portion = if include_numbers { word_count * 20 / 100 } else { 0 }; // 20% numbers
     let symbol_portion = if include_symbols { word_count * 10 / 100 } else { 0 }; // 10% symbols
-    
-    if word_portion > 0 {
-        parts.push(generate_random_words(word_portion));
-    }
-    
-    if number_portion > 0 {
-        parts.push(generate_number_practice(number_portion));
-    }
-    
-    if symbol_portion > 0 {
-        parts.push(generate_symbol_practice(symbol_portion));
+    let word_portion = word_count - number_portion - symbol_portion; // ~70% words
+
+    let mut parts = Vec::new();
+    for text in [
+        generate_random_words(word_portion, rng),
+        generate_number_practice(number_portion, rng),
+        generate_symbol_practice(symbol_portion, rng),
+    ] {
+        parts.extend(text.split_whitespace().map(str::to_string));
     }
-    
-    let mut rng = thread_rng();
-    parts.shuffle(&mut rng);
+
+    parts.shuffle(rng);
     parts.join(" ")
 }
 
@@ -266,6 +449,17 @@ pub fn get_difficulty_description(score: f64) -> &'static str {
     }
 }
 
+/// Number of user-perceived characters (grapheme clusters) in `text`. Typing
+/// position, progress and char counts are all measured in these.
+pub fn grapheme_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Terminal columns `text` takes up; wide CJK and emoji count as two.
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
 pub fn wrap_text_to_width(text: &str, width: usize) -> Vec<String> {
     let words: Vec<&str> = text.split_whitespace().collect();
     let mut lines = Vec::new();
@@ -274,7 +468,7 @@ pub fn wrap_text_to_width(text: &str, width: usize) -> Vec<String> {
     for word in words {
         if current_line.is_empty() {
             current_line = word.to_string();
-        } else if current_line.len() + 1 + word.len() <= width {
+        } else if display_width(&current_line) + 1 + display_width(word) <= width {
             current_line.push(' ');
             current_line.push_str(word);
         } else {
@@ -297,16 +491,55 @@ pub fn estimate_typing_time(text: &str, wpm: f64) -> std::time::Duration {
 }
 
 pub fn sanitize_text(text: &str) -> String {
-    text.chars().filter(|c| c.is_ascii() && (*c == ' ' || *c == '\n' || c.is_ascii_graphic())).collect::<String>().trim().to_string()
+    text.chars().filter(|c| *c == '\n' || !c.is_control()).collect::<String>().trim().to_string()
+}
+
+#[derive(Debug, Clone)]
+pub struct TextLoadOptions {
+    pub normalize_whitespace: bool,
+    pub max_length: Option<usize>,
+}
+
+/// Cleans up externally loaded text so it can be used as a `TestMode::Text` target.
+pub fn prepare_custom_text(raw: &str, options: &TextLoadOptions) -> String {
+    let text = if options.normalize_whitespace {
+        raw.split_whitespace().collect::<Vec<_>>().join(" ")
+    } else {
+        raw.replace("\r\n", "\n")
+    };
+    let text = sanitize_text(&text);
+
+    match options.max_length {
+        Some(max_length) if grapheme_count(&text) > max_length => {
+            let cut = text.grapheme_indices(true).nth(max_length).map_or(text.len(), |(i, _)| i);
+            let mut truncated = &text[..cut];
+            // Cut back to the last whole word so the test doesn't end mid-word
+            if !text[cut..].starts_with(char::is_whitespace) {
+                truncated = truncated.rfind(char::is_whitespace).map_or(truncated, |end| &truncated[..end]);
+            }
+            truncated.trim_end().to_string()
+        }
+        _ => text,
+    }
+}
+
+pub fn load_text_file(path: &std::path::Path, options: &TextLoadOptions) -> Result<String, Box<dyn std::error::Error>> {
+    let raw = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let text = prepare_custom_text(&raw, options);
+    if text.is_empty() {
+        return Err(format!("{} contains no typeable text", path.display()).into());
+    }
+    Ok(text)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
 
     #[test]
     fn test_generate_random_words() {
-        let text = generate_random_words(10);
+        let text = generate_random_words(10, &mut thread_rng());
         let words: Vec<&str> = text.split_whitespace().collect();
         assert_eq!(words.len(), 10);
         for word in words {
@@ -316,18 +549,52 @@ mod tests {
 
     #[test]
     fn test_generate_words_with_difficulty() {
-        let easy_text = generate_words_with_difficulty(5, WordDifficulty::Easy);
-        let hard_text = generate_words_with_difficulty(5, WordDifficulty::Hard);
-        let prog_text = generate_words_with_difficulty(5, WordDifficulty::Programming);
+        let easy_text = generate_words_with_difficulty(5, WordDifficulty::Easy, None, &mut thread_rng());
+        let hard_text = generate_words_with_difficulty(5, WordDifficulty::Hard, None, &mut thread_rng());
+        let prog_text = generate_words_with_difficulty(5, WordDifficulty::Programming, None, &mut thread_rng());
         assert_eq!(easy_text.split_whitespace().count(), 5);
         assert_eq!(hard_text.split_whitespace().count(), 5);
         assert_eq!(prog_text.split_whitespace().count(), 5);
     }
 
+    #[test]
+    fn test_generate_ngram_practice() {
+        let ngrams = vec!["th".to_string(), "qz".to_string()];
+        let text = generate_ngram_practice(&ngrams, 40, &mut thread_rng());
+        assert_eq!(text.split_whitespace().count(), 40);
+        assert!(text.split_whitespace().all(|word| word.contains("th") || word.contains("qz")));
+    }
+
+    #[test]
+    fn test_generate_restricted_practice() {
+        let allowed: Vec<char> = "asdfjkl;".chars().collect();
+        let text = generate_restricted_practice(&allowed, &['j'], 40, &mut thread_rng());
+        assert_eq!(text.split_whitespace().count(), 40);
+        assert!(text.chars().all(|c| c == ' ' || allowed.contains(&c)));
+        assert!(text.split_whitespace().all(|word| word.chars().count() >= 1));
+    }
+
+    #[test]
+    fn test_generate_from_source_word_counts() {
+        for source in WordSource::all() {
+            let text = generate_from_source(&source, 23, None, &mut thread_rng());
+            assert_eq!(text.split_whitespace().count(), 23, "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_words() {
+        for source in WordSource::all() {
+            let first = generate_from_source(&source, 30, None, &mut StdRng::seed_from_u64(42));
+            let second = generate_from_source(&source, 30, None, &mut StdRng::seed_from_u64(42));
+            assert_eq!(first, second, "{:?}", source);
+        }
+    }
+
     #[test]
     fn test_focused_practice() {
         let target_chars = vec!['a', 'e', 'i', 'o', 'u'];
-        let text = generate_focused_practice(&target_chars, 10);
+        let text = generate_focused_practice(&target_chars, 10, &mut thread_rng());
         let words: Vec<&str> = text.split_whitespace().collect();
         
         assert_eq!(words.len(), 10);
@@ -358,6 +625,18 @@ mod tests {
         for line in &lines {
             assert!(line.len() <= 20);
         }
+
+        // Wide chars take two columns each
+        let lines = wrap_text_to_width("日本語 日本語 テスト", 14);
+        assert_eq!(lines, vec!["日本語 日本語", "テスト"]);
+    }
+
+    #[test]
+    fn test_grapheme_count_and_width() {
+        assert_eq!(grapheme_count("cafe\u{301}"), 4);
+        assert_eq!(grapheme_count("👍🏽 ok"), 4);
+        assert_eq!(display_width("日本"), 4);
+        assert_eq!(display_width("cafe\u{301}"), 4);
     }
 
     #[test]
@@ -366,6 +645,20 @@ mod tests {
         let clean_text = sanitize_text(dirty_text);
         
         assert_eq!(clean_text, "HelloWorld!!!");
+        assert_eq!(sanitize_text("Grüße, 世界"), "Grüße, 世界");
+    }
+
+    #[test]
+    fn test_prepare_custom_text() {
+        let raw = "  First line\r\n\tsecond   line\n\nthird line  ";
+        let normalized = TextLoadOptions { normalize_whitespace: true, max_length: None };
+        assert_eq!(prepare_custom_text(raw, &normalized), "First line second line third line");
+
+        let limited = TextLoadOptions { normalize_whitespace: true, max_length: Some(14) };
+        assert_eq!(prepare_custom_text(raw, &limited), "First line");
+
+        let exact = TextLoadOptions { normalize_whitespace: true, max_length: Some(10) };
+        assert_eq!(prepare_custom_text(raw, &exact), "First line");
     }
 
     #[test]
@@ -0,0 +1,231 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::key_profile::WordBias;
+
+const SPANISH_WORDS: &[&str] = &[
+    "de", "la", "que", "el", "en", "y", "a", "los", "se", "del",
+    "las", "un", "por", "con", "no", "una", "su", "para", "es", "al",
+    "lo", "como", "más", "pero", "sus", "le", "ya", "o", "este", "sí",
+    "porque", "esta", "entre", "cuando", "muy", "sin", "sobre", "también", "me", "hasta",
+    "hay", "donde", "quien", "desde", "todo", "nos", "durante", "todos", "uno", "les",
+    "ni", "contra", "otros", "ese", "eso", "ante", "ellos", "esto", "antes", "algunos",
+    "qué", "unos", "yo", "otro", "otras", "otra", "él", "tanto", "esa", "estos",
+    "mucho", "nada", "muchos", "cual", "poco", "ella", "estar", "haber", "año", "día",
+    "tiempo", "vida", "casa", "mundo", "país", "agua", "hacer", "decir", "ver", "dar",
+];
+
+const GERMAN_WORDS: &[&str] = &[
+    "der", "die", "und", "in", "den", "von", "zu", "das", "mit", "sich",
+    "des", "auf", "für", "ist", "im", "dem", "nicht", "ein", "eine", "als",
+    "auch", "es", "an", "werden", "aus", "er", "hat", "dass", "sie", "nach",
+    "wird", "bei", "einer", "um", "am", "sind", "noch", "wie", "einem", "über",
+    "einen", "so", "zum", "war", "haben", "nur", "oder", "aber", "vor", "zur",
+    "bis", "mehr", "durch", "man", "sein", "wurde", "sei", "in", "prozent", "hatte",
+    "kann", "gegen", "vom", "können", "schon", "wenn", "habe", "seine", "mark", "ihre",
+    "dann", "unter", "wir", "soll", "ich", "eines", "jahr", "zwei", "jahren", "diese",
+    "zeit", "haus", "welt", "straße", "mädchen", "schön", "groß", "früh", "müssen", "würde",
+];
+
+const FRENCH_WORDS: &[&str] = &[
+    "de", "la", "le", "et", "les", "des", "en", "un", "du", "une",
+    "que", "est", "pour", "qui", "dans", "par", "plus", "pas", "au", "sur",
+    "ne", "se", "ce", "il", "sont", "avec", "son", "aux", "mais", "comme",
+    "ou", "elle", "nous", "vous", "ils", "leur", "été", "être", "avoir", "fait",
+    "faire", "dit", "tout", "deux", "même", "entre", "après", "aussi", "bien", "où",
+    "sans", "peut", "très", "alors", "déjà", "encore", "toujours", "jamais", "rien", "chose",
+    "temps", "jour", "année", "monde", "vie", "homme", "femme", "enfant", "main", "tête",
+    "père", "mère", "à", "là", "ça", "leçon", "français", "garçon", "fenêtre", "forêt",
+];
+
+const PORTUGUESE_WORDS: &[&str] = &[
+    "de", "a", "o", "que", "e", "do", "da", "em", "um", "para",
+    "é", "com", "não", "uma", "os", "no", "se", "na", "por", "mais",
+    "as", "dos", "como", "mas", "foi", "ao", "ele", "das", "tem", "à",
+    "seu", "sua", "ou", "ser", "quando", "muito", "há", "nos", "já", "está",
+    "eu", "também", "só", "pelo", "pela", "até", "isso", "ela", "entre", "era",
+    "depois", "sem", "mesmo", "aos", "ter", "seus", "quem", "nas", "me", "esse",
+    "eles", "estão", "você", "tinha", "foram", "essa", "num", "nem", "suas", "meu",
+    "tempo", "ano", "dia", "casa", "vida", "mundo", "coração", "não", "irmã", "avó",
+];
+
+const BUNDLED_WORD_LISTS: &[(&str, &[&str])] = &[
+    ("Spanish", SPANISH_WORDS),
+    ("German", GERMAN_WORDS),
+    ("French", FRENCH_WORDS),
+    ("Portuguese", PORTUGUESE_WORDS),
+];
+
+/// A named vocabulary with an optional frequency weight per word.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordList {
+    pub name: String,
+    words: Vec<(String, f64)>,
+}
+
+// A JSON entry is either a bare word or a word with a weight
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonEntry {
+    Word(String),
+    Weighted { word: String, weight: f64 },
+}
+
+impl WordList {
+    pub fn new(name: &str, words: Vec<(String, f64)>) -> Self {
+        let words = words
+            .into_iter()
+            .map(|(word, weight)| (word.trim().to_string(), weight))
+            .filter(|(word, weight)| !word.is_empty() && !word.contains(char::is_whitespace) && *weight > 0.0)
+            .collect();
+        Self { name: name.to_string(), words }
+    }
+
+    /// Parses a JSON array of words or of `{ "word": ..., "weight": ... }` objects.
+    pub fn from_json(name: &str, content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let entries: Vec<JsonEntry> = serde_json::from_str(content)?;
+        let words = entries
+            .into_iter()
+            .map(|entry| match entry {
+                JsonEntry::Word(word) => (word, 1.0),
+                JsonEntry::Weighted { word, weight } => (word, weight),
+            })
+            .collect();
+        Ok(Self::new(name, words))
+    }
+
+    /// Parses one word per line, optionally followed by a weight. Lines
+    /// starting with `#` are comments.
+    pub fn from_text(name: &str, content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut words = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let word = parts.next().unwrap_or_default().to_string();
+            let weight = match parts.next() {
+                Some(weight) => weight.parse().map_err(|_| format!("line {}: invalid weight '{}'", number + 1, weight))?,
+                None => 1.0,
+            };
+            words.push((word, weight));
+        }
+        Ok(Self::new(name, words))
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Picks `count` words by weight, further favouring weak keys when a `bias` is given.
+    pub fn generate(&self, count: usize, bias: Option<&WordBias>, rng: &mut impl Rng) -> String {
+        let mut words = Vec::new();
+        for _ in 0..count {
+            let picked = self.words.choose_weighted(rng, |(word, weight)| {
+                weight * bias.map_or(1.0, |bias| bias.word_weight(word))
+            });
+            if let Ok((word, _)) = picked {
+                words.push(word.as_str());
+            }
+        }
+        words.join(" ")
+    }
+}
+
+/// Bundled languages plus any lists in the `wordlists` config folder.
+#[derive(Debug, Clone, Default)]
+pub struct WordListLibrary {
+    lists: Vec<WordList>,
+}
+
+impl WordListLibrary {
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut library = Self::bundled();
+        let lists_dir = Self::get_lists_dir()?;
+        if lists_dir.is_dir() {
+            let mut paths: Vec<PathBuf> = fs::read_dir(&lists_dir)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json" || ext == "txt"))
+                .collect();
+            paths.sort();
+            for path in paths {
+                let list = Self::load_file(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                library.add(list);
+            }
+        }
+        Ok(library)
+    }
+
+    pub fn bundled() -> Self {
+        let lists = BUNDLED_WORD_LISTS
+            .iter()
+            .map(|(name, words)| WordList::new(name, words.iter().map(|word| (word.to_string(), 1.0)).collect()))
+            .collect();
+        Self { lists }
+    }
+
+    fn load_file(path: &Path) -> Result<WordList, Box<dyn std::error::Error>> {
+        let name = path.file_stem().ok_or("missing file name")?.to_string_lossy();
+        let content = fs::read_to_string(path)?;
+        let list = if path.extension().is_some_and(|ext| ext == "json") {
+            WordList::from_json(&name, &content)?
+        } else {
+            WordList::from_text(&name, &content)?
+        };
+        if list.len() == 0 {
+            return Err("word list is empty".into());
+        }
+        Ok(list)
+    }
+
+    /// Adds a list, replacing any existing one with the same name.
+    pub fn add(&mut self, list: WordList) {
+        self.lists.retain(|existing| !existing.name.eq_ignore_ascii_case(&list.name));
+        self.lists.push(list);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&WordList> {
+        self.lists.iter().find(|list| list.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.lists.iter().map(|list| list.name.clone()).collect()
+    }
+
+    fn get_lists_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let mut path = dirs::config_dir().ok_or("Could not find config directory")?;
+        path.push("typing-test");
+        path.push("wordlists");
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_parse_word_lists() {
+        let json = WordList::from_json("terms", r#"["widget", {"word": "gadget", "weight": 3}]"#).unwrap();
+        assert_eq!(json.words, vec![("widget".to_string(), 1.0), ("gadget".to_string(), 3.0)]);
+
+        let text = WordList::from_text("terms", "# product terms\nwidget\ngadget 2.5\n\n").unwrap();
+        assert_eq!(text.words, vec![("widget".to_string(), 1.0), ("gadget".to_string(), 2.5)]);
+        assert!(WordList::from_text("terms", "widget lots").is_err());
+    }
+
+    #[test]
+    fn test_library_lookup_and_generation() {
+        let mut library = WordListLibrary::bundled();
+        assert!(library.get("german").is_some());
+        library.add(WordList::new("German", vec![("hallo".to_string(), 1.0)]));
+        let german = library.get("German").unwrap();
+        assert_eq!(german.generate(5, None, &mut thread_rng()), "hallo hallo hallo hallo hallo");
+        assert_eq!(library.names().iter().filter(|name| *name == "German").count(), 1);
+    }
+}